@@ -0,0 +1,49 @@
+/* src/controller/apply.rs */
+
+//!
+//! Apply hooks and the rollback of values the application rejected.
+
+use std::fmt::Display;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use atomhold::{Entry, Store};
+
+/// Future returned by an apply hook, with the error already rendered.
+pub(crate) type ApplyFuture = Pin<Box<dyn Future<Output = Result<(), String>> + Send>>;
+
+/// Hands a new value (and the one it replaces) to the application.
+pub(crate) type ApplyFn<T> = Arc<dyn Fn(Option<Arc<T>>, Arc<T>) -> ApplyFuture + Send + Sync>;
+
+/// Boxes a user supplied `on_apply` hook.
+pub(crate) fn apply_fn<T, F, Fut, E>(f: F) -> ApplyFn<T>
+where
+	F: Fn(Option<Arc<T>>, Arc<T>) -> Fut + Send + Sync + 'static,
+	Fut: Future<Output = Result<(), E>> + Send + 'static,
+	E: Display,
+{
+	Arc::new(move |old, new| {
+		let applied = f(old, new);
+		Box::pin(async move { applied.await.map_err(|e| e.to_string()) })
+	})
+}
+
+/// Puts `previous` back in place of a value that failed to apply.
+///
+/// Without a previous entry the key is removed again.
+pub(crate) fn restore<T>(store: &Store<T>, key: &str, previous: Option<Entry<T>>)
+where
+	T: Clone + Send + Sync,
+{
+	match previous {
+		Some(entry) => {
+			let value = (*entry.value).clone();
+			store.insert(key.to_string(), value, entry.meta.source, entry.meta.policy);
+		}
+		None => {
+			// Persistent entries refuse removal and stay in place
+			let _ = store.remove(key);
+		}
+	}
+}
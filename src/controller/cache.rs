@@ -0,0 +1,138 @@
+/* src/controller/cache.rs */
+
+//!
+//! On-disk cache of the last successfully loaded document per key.
+
+use std::path::{Path, PathBuf};
+
+use fmtstruct::{PreProcess, ValidateConfig};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tokio::fs;
+
+use super::LiveError;
+use super::document::decode;
+use super::write::{EXTENSIONS, deserialize, serialize};
+
+/// Where a loaded value came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadStatus {
+	/// Loaded from the configured sources.
+	Source,
+	/// The sources were invalid or missing, so the last-known-good value from the
+	/// cache was used. `reason` describes the source error.
+	Cached { reason: String },
+}
+
+impl LoadStatus {
+	/// Returns true if the value came from the last-known-good cache.
+	pub fn is_cached(&self) -> bool {
+		matches!(self, Self::Cached { .. })
+	}
+}
+
+/// A directory holding one document per key, as `<key>.<ext>`.
+///
+/// Each document is stored in the format of the file it was loaded from.
+/// Documents are stored after merging, so they include environment overrides
+/// and interpolated values.
+#[derive(Debug, Clone)]
+pub(crate) struct LastGood {
+	dir: PathBuf,
+}
+
+impl LastGood {
+	pub fn new(dir: PathBuf) -> Self {
+		Self { dir }
+	}
+
+	fn path(&self, key: &str, extension: &str) -> PathBuf {
+		self.dir.join(format!("{}.{}", key, extension))
+	}
+
+	/// Stores the document of `key`, loaded from `source`, replacing the
+	/// previous one atomically.
+	///
+	/// Fails if the format of `source` cannot be written (e.g. postcard).
+	pub async fn save(&self, key: &str, document: &Value, source: &Path) -> Result<(), LiveError> {
+		let extension = source.extension().and_then(|e| e.to_str()).unwrap_or("");
+		let path = self.path(key, extension);
+		let bytes = serialize(document, &path)?;
+		if let Some(parent) = path.parent() {
+			fs::create_dir_all(parent).await?;
+		}
+		let tmp = path.with_extension(format!("{}.tmp", extension));
+		write_private(&tmp, &bytes).await?;
+		fs::rename(&tmp, &path).await?;
+		// The source may have switched formats
+		self.remove_except(key, Some(extension)).await
+	}
+
+	/// Removes the cached document of `key`, if any.
+	pub async fn remove(&self, key: &str) -> Result<(), LiveError> {
+		self.remove_except(key, None).await
+	}
+
+	async fn remove_except(&self, key: &str, keep: Option<&str>) -> Result<(), LiveError> {
+		for extension in EXTENSIONS.iter().filter(|e| Some(**e) != keep) {
+			match fs::remove_file(self.path(key, extension)).await {
+				Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+				_ => {}
+			}
+		}
+		Ok(())
+	}
+
+	/// Decodes and validates the cached document of `key`.
+	///
+	/// Returns the value and the cache file, or `None` if nothing is cached.
+	pub async fn load<T>(&self, key: &str) -> Result<Option<(T, PathBuf)>, LiveError>
+	where
+		T: DeserializeOwned + PreProcess + ValidateConfig,
+	{
+		for extension in EXTENSIONS {
+			let path = self.path(key, extension);
+			let bytes = match fs::read(&path).await {
+				Ok(bytes) => bytes,
+				Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+				Err(e) => return Err(e.into()),
+			};
+			let document = deserialize(&bytes, &path)?;
+			return Ok(Some((decode::<T>(document, key)?, path)));
+		}
+		Ok(None)
+	}
+
+	/// Returns the keys of all cached documents.
+	pub async fn keys(&self) -> Result<Vec<String>, LiveError> {
+		let mut keys = Vec::new();
+		let mut entries = match fs::read_dir(&self.dir).await {
+			Ok(entries) => entries,
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(keys),
+			Err(e) => return Err(e.into()),
+		};
+		while let Some(entry) = entries.next_entry().await? {
+			let name = entry.file_name();
+			let path = Path::new(&name);
+			if let (Some(key), Some(extension)) = (path.file_stem(), path.extension())
+				&& EXTENSIONS.iter().any(|e| *e == extension)
+			{
+				keys.push(key.to_string_lossy().into_owned());
+			}
+		}
+		keys.sort();
+		keys.dedup();
+		Ok(keys)
+	}
+}
+
+/// Writes a file only the current user can read, as documents may hold secrets.
+async fn write_private(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+	// Create the file with restricted permissions before any content lands in it
+	let mut options = fs::OpenOptions::new();
+	options.write(true).create(true).truncate(true);
+	#[cfg(unix)]
+	options.mode(0o600);
+	drop(options.open(path).await?);
+	fs::write(path, bytes).await
+}
@@ -0,0 +1,163 @@
+/* src/controller/diff.rs */
+
+//!
+//! Field-level differences between two versions of a configuration.
+
+use std::fmt;
+
+use serde_json::Value;
+
+/// A single changed field.
+///
+/// `old` is `None` for added fields and `new` is `None` for removed ones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+	/// Dotted path of the field, e.g. `db.port` or `listeners[0].bind`.
+	pub path: String,
+	pub old: Option<Value>,
+	pub new: Option<Value>,
+}
+
+impl FieldChange {
+	/// Returns true if the field did not exist before.
+	pub fn is_added(&self) -> bool {
+		self.old.is_none()
+	}
+
+	/// Returns true if the field no longer exists.
+	pub fn is_removed(&self) -> bool {
+		self.new.is_none()
+	}
+}
+
+impl fmt::Display for FieldChange {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match (&self.old, &self.new) {
+			(Some(old), Some(new)) => write!(f, "{} {} → {}", self.path, old, new),
+			(None, Some(new)) => write!(f, "{} added {}", self.path, new),
+			(Some(old), None) => write!(f, "{} removed {}", self.path, old),
+			(None, None) => write!(f, "{}", self.path),
+		}
+	}
+}
+
+/// Field-level difference between the previous and the new configuration.
+///
+/// Computed on the merged document a value was decoded from, so it reflects every
+/// source (layers, defaults, includes, overrides) but not serde defaults. Values
+/// loaded from non self-describing formats (e.g. postcard) have no document and
+/// always produce an empty diff.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigDiff {
+	/// Changed leaf fields, ordered by path.
+	pub changes: Vec<FieldChange>,
+}
+
+impl ConfigDiff {
+	/// Computes the difference between two documents.
+	///
+	/// A missing (`null`) document counts as an empty object.
+	pub(crate) fn between(old: &Value, new: &Value) -> Self {
+		let empty = Value::Object(Default::default());
+		let old = if old.is_null() { &empty } else { old };
+		let new = if new.is_null() { &empty } else { new };
+		let mut changes = Vec::new();
+		diff_values(String::new(), Some(old), Some(new), &mut changes);
+		changes.sort_by(|a, b| a.path.cmp(&b.path));
+		Self { changes }
+	}
+
+	/// Returns true if nothing changed.
+	pub fn is_empty(&self) -> bool {
+		self.changes.is_empty()
+	}
+
+	/// Returns the fields that were added.
+	pub fn added(&self) -> impl Iterator<Item = &FieldChange> {
+		self.changes.iter().filter(|c| c.is_added())
+	}
+
+	/// Returns the fields that were removed.
+	pub fn removed(&self) -> impl Iterator<Item = &FieldChange> {
+		self.changes.iter().filter(|c| c.is_removed())
+	}
+
+	/// Returns the fields whose value changed.
+	pub fn changed(&self) -> impl Iterator<Item = &FieldChange> {
+		self
+			.changes
+			.iter()
+			.filter(|c| !c.is_added() && !c.is_removed())
+	}
+
+	/// Returns the change of the field at `path`, if any.
+	pub fn get(&self, path: &str) -> Option<&FieldChange> {
+		self.changes.iter().find(|c| c.path == path)
+	}
+}
+
+impl fmt::Display for ConfigDiff {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for (i, change) in self.changes.iter().enumerate() {
+			if i > 0 {
+				f.write_str(", ")?;
+			}
+			write!(f, "{}", change)?;
+		}
+		Ok(())
+	}
+}
+
+fn diff_values(path: String, old: Option<&Value>, new: Option<&Value>, out: &mut Vec<FieldChange>) {
+	match (old, new) {
+		(Some(Value::Object(old)), Some(Value::Object(new))) => {
+			for (key, value) in old {
+				diff_values(join(&path, key), Some(value), new.get(key), out);
+			}
+			for (key, value) in new {
+				if !old.contains_key(key) {
+					diff_values(join(&path, key), None, Some(value), out);
+				}
+			}
+		}
+		(Some(Value::Array(old)), Some(Value::Array(new))) => {
+			for i in 0..old.len().max(new.len()) {
+				diff_values(format!("{}[{}]", path, i), old.get(i), new.get(i), out);
+			}
+		}
+		(Some(old), Some(new)) if old == new => {}
+		(old, new) => out.push(FieldChange {
+			path,
+			old: old.cloned(),
+			new: new.cloned(),
+		}),
+	}
+}
+
+fn join(path: &str, key: &str) -> String {
+	if path.is_empty() {
+		key.to_string()
+	} else {
+		format!("{}.{}", path, key)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn test_diff_paths() {
+		let old = json!({"tls": false, "db": {"port": 5432, "host": "a"}, "tags": ["x"]});
+		let new = json!({"tls": true, "db": {"port": 5432}, "tags": ["x", "y"], "bind": ":443"});
+		let diff = ConfigDiff::between(&old, &new);
+
+		let paths: Vec<_> = diff.changes.iter().map(|c| c.path.as_str()).collect();
+		assert_eq!(paths, vec!["bind", "db.host", "tags[1]", "tls"]);
+		assert_eq!(diff.added().count(), 2);
+		assert_eq!(diff.removed().count(), 1);
+		assert_eq!(diff.get("tls").unwrap().to_string(), "tls false → true");
+		assert!(ConfigDiff::between(&old, &old).is_empty());
+	}
+}
@@ -5,21 +5,50 @@
 
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use atomhold::{Store, UnloadPolicy};
-use fmtstruct::{DynLoader, FmtError, LoadResult, PreProcess, ValidateConfig};
+use atomhold::{Entry, Store, UnloadPolicy};
+use fmtstruct::format::AnyFormat;
+use fmtstruct::{DynLoader, FmtError, Format, LoadResult, PreProcess, ValidateConfig};
+use futures_util::{StreamExt, stream};
+use serde::Serialize;
 use serde::de::DeserializeOwned;
+use serde_json::Value;
 use tokio::fs;
 use tokio::sync::RwLock;
 
 #[cfg(feature = "signal")]
-use fsig::{Config as WatcherConfig, Target, Watcher};
+use fsig::{Config as WatcherConfig, Target};
 
 use super::LiveError;
+use super::apply::{ApplyFn, apply_fn};
+use super::cache::LastGood;
+use super::diff::ConfigDiff;
+use super::document::{Document, content_hash, decode, merge, patch};
+use super::edit;
+use super::env::EnvOverrides;
+use super::history::{DEFAULT_HISTORY_LIMIT, History, Revision};
+use super::include::expand;
+use super::inherit::Inheritance;
+use super::interpolate::interpolate;
+use super::live::EqualFn;
+use super::overrides::{Override, Overrides};
+use super::pattern::{CommitMode, KeyPattern, Rejection, ScanMode, ScanResult};
+use super::reload::{ReloadState, Reloads};
 #[cfg(feature = "signal")]
-use super::WatchState;
-use super::pattern::{KeyPattern, ScanMode, ScanResult};
+use super::watch::{Change, Rescan, WatchSet, pending_target};
+use super::write::{Writes, check_key, check_sandbox, deserialize, serialize};
+#[cfg(feature = "signal")]
+use super::{WatchState, WatchStatus};
+
+/// Cross-entry validation hook, see [`LiveDirBuilder::validate_all`].
+type ValidateAllFn<T> =
+	Arc<dyn Fn(&HashMap<String, Arc<T>>) -> Result<(), Rejection> + Send + Sync>;
+
+/// Number of entries loaded concurrently unless configured otherwise.
+const DEFAULT_CONCURRENCY: usize = 16;
 
 /// A controller for live-reloading a directory of configurations.
 ///
@@ -30,32 +59,59 @@ use super::pattern::{KeyPattern, ScanMode, ScanResult};
 /// stopped and the background task aborted when the last remaining instance is dropped
 /// or when `stop_watching` is called on the last instance holding the active watcher.
 pub struct LiveDir<T> {
+	inner: Arc<DirInner<T>>,
+	#[cfg(feature = "signal")]
+	watch_state: Option<Arc<WatchState>>,
+}
+
+/// State shared by all clones of a `LiveDir` and its watch task.
+struct DirInner<T> {
 	store: Arc<Store<T>>,
 	loader: Arc<DynLoader>,
 	path: PathBuf,
 	pattern: KeyPattern,
 	scan_mode: ScanMode,
 	policy: UnloadPolicy,
+	commit_mode: CommitMode,
 	max_entries: Option<usize>,
+	concurrency: usize,
+	env: Option<EnvOverrides>,
+	defaults_file: Option<String>,
+	inheritance: Option<Inheritance>,
+	interpolate: bool,
+	equal: Option<EqualFn<T>>,
+	validate_all: Option<ValidateAllFn<T>>,
+	on_apply: Option<ApplyFn<T>>,
+	history: Mutex<History<T>>,
+	last_good: Option<LastGood>,
+	write_format: Option<AnyFormat>,
+	overrides: Arc<Overrides<T>>,
+	writes: Writes,
+	reloads: Reloads<ScanResult>,
 	/// Keys owned by this LiveDir instance (prevents cross-deletion with shared Store).
-	owned_keys: Arc<RwLock<HashSet<String>>>,
+	owned_keys: RwLock<HashSet<String>>,
+	/// What each key's last successful load was built from.
+	entries: Mutex<HashMap<String, EntryState>>,
+	/// Files included by the defaults file, which affect every entry.
+	#[cfg_attr(not(feature = "signal"), allow(dead_code))]
+	defaults_includes: Mutex<Vec<PathBuf>>,
+	/// Raw documents of the last scan, reused by partial reloads.
+	raw: Mutex<RawCache>,
+	/// Set when a rejected atomic scan left changes unapplied, so the next reload
+	/// has to look at every entry.
+	rescan: AtomicBool,
+	#[cfg_attr(not(feature = "signal"), allow(dead_code))]
 	on_error: Option<Arc<dyn Fn(LiveError) + Send + Sync>>,
 	#[cfg(feature = "signal")]
-	watch_state: Option<Arc<WatchState>>,
+	rescan_timer: Option<Rescan>,
+	#[cfg(feature = "signal")]
+	poll_interval: Option<Duration>,
 }
 
 impl<T> Clone for LiveDir<T> {
 	fn clone(&self) -> Self {
 		Self {
-			store: self.store.clone(),
-			loader: self.loader.clone(),
-			path: self.path.clone(),
-			pattern: self.pattern.clone(),
-			scan_mode: self.scan_mode.clone(),
-			policy: self.policy,
-			max_entries: self.max_entries,
-			owned_keys: self.owned_keys.clone(),
-			on_error: self.on_error.clone(),
+			inner: self.inner.clone(),
 			#[cfg(feature = "signal")]
 			watch_state: self.watch_state.clone(),
 		}
@@ -77,8 +133,26 @@ pub struct LiveDirBuilder<T> {
 	pattern: KeyPattern,
 	scan_mode: ScanMode,
 	policy: UnloadPolicy,
+	commit_mode: CommitMode,
 	max_entries: Option<usize>,
+	concurrency: usize,
+	env: Option<EnvOverrides>,
+	defaults_file: Option<String>,
+	inheritance: Option<Inheritance>,
+	interpolate: bool,
+	equal: Option<EqualFn<T>>,
+	validate_all: Option<ValidateAllFn<T>>,
+	on_apply: Option<ApplyFn<T>>,
+	history_limit: usize,
+	last_good: Option<LastGood>,
+	write_format: Option<AnyFormat>,
 	on_error: Option<Arc<dyn Fn(LiveError) + Send + Sync>>,
+	#[cfg(feature = "signal")]
+	rescan_interval: Option<Duration>,
+	#[cfg(feature = "signal")]
+	rescan_jitter: Duration,
+	#[cfg(feature = "signal")]
+	poll_interval: Option<Duration>,
 }
 
 impl<T> LiveDirBuilder<T>
@@ -93,8 +167,26 @@ where
 			pattern: KeyPattern::default(),
 			scan_mode: ScanMode::default(),
 			policy: UnloadPolicy::default(),
+			commit_mode: CommitMode::default(),
 			max_entries: None,
+			concurrency: DEFAULT_CONCURRENCY,
+			env: None,
+			defaults_file: None,
+			inheritance: None,
+			interpolate: false,
+			equal: None,
+			validate_all: None,
+			on_apply: None,
+			history_limit: DEFAULT_HISTORY_LIMIT,
+			last_good: None,
+			write_format: None,
 			on_error: None,
+			#[cfg(feature = "signal")]
+			rescan_interval: None,
+			#[cfg(feature = "signal")]
+			rescan_jitter: Duration::ZERO,
+			#[cfg(feature = "signal")]
+			poll_interval: None,
 		}
 	}
 
@@ -128,6 +220,14 @@ where
 		self
 	}
 
+	/// Sets how a scan's changes are written to the store.
+	///
+	/// See [`CommitMode`]. Defaults to `Partial`.
+	pub fn commit_mode(mut self, mode: CommitMode) -> Self {
+		self.commit_mode = mode;
+		self
+	}
+
 	/// Set maximum number of entries to load from the directory.
 	/// If exceeded, returns an error during scan.
 	pub fn max_entries(mut self, max: usize) -> Self {
@@ -135,6 +235,152 @@ where
 		self
 	}
 
+	/// Sets how many entries are loaded concurrently during a scan.
+	///
+	/// Defaults to 16. Zero is treated as one.
+	pub fn concurrency(mut self, limit: usize) -> Self {
+		self.concurrency = limit.max(1);
+		self
+	}
+
+	/// Applies environment variable overrides to every entry.
+	///
+	/// The first segment after the prefix selects the entry key.
+	pub fn env(mut self, env: EnvOverrides) -> Self {
+		self.env = Some(env);
+		self
+	}
+
+	/// Sets a defaults file in the directory root that is deep-merged under every entry.
+	///
+	/// With an extension (e.g. `_defaults.json`) the file is loaded as-is, otherwise
+	/// all registered formats are probed. The file is never exposed as a key, and
+	/// changing it reloads every entry.
+	pub fn defaults_file(mut self, name: impl Into<String>) -> Self {
+		self.defaults_file = Some(name.into());
+		self
+	}
+
+	/// Enables `extends` inheritance between entries.
+	///
+	/// Inheritance cycles, missing parents and failed parents are reported per key
+	/// in `ScanResult::failed`. A changed parent reloads its dependents.
+	pub fn inheritance(mut self, inheritance: Inheritance) -> Self {
+		self.inheritance = Some(inheritance);
+		self
+	}
+
+	/// Resolves `${VAR}`, `${VAR:-default}` and `${file:path}` references in every entry.
+	///
+	/// Relative file paths are resolved against the entry's directory, and referenced
+	/// files are watched, also while missing. Files outside of the directory fail
+	/// the entry with `LiveError::Interpolate`, and unresolved references with
+	/// `LiveError::Unresolved`.
+	pub fn interpolate(mut self, enabled: bool) -> Self {
+		self.interpolate = enabled;
+		self
+	}
+
+	/// Also skips reloads of entries whose decoded value equals the current one.
+	///
+	/// Entries with byte-identical content are always skipped; this additionally
+	/// catches changes that do not affect `T`.
+	pub fn skip_equal(mut self) -> Self
+	where
+		T: PartialEq,
+	{
+		self.equal = Some(Arc::new(|a: &T, b: &T| a == b));
+		self
+	}
+
+	/// Validates all entries together, after each one passed `ValidateConfig`.
+	///
+	/// The hook receives every entry as it will be after the scan. Keys it rejects
+	/// keep their old value and are reported in `ScanResult::failed`; rejecting the
+	/// whole set discards every change of the scan.
+	pub fn validate_all<F>(mut self, f: F) -> Self
+	where
+		F: Fn(&HashMap<String, Arc<T>>) -> Result<(), Rejection> + Send + Sync + 'static,
+	{
+		self.validate_all = Some(Arc::new(f));
+		self
+	}
+
+	/// Hands every added or updated entry to the application once it is stored.
+	///
+	/// The hook receives the previous value (if any) and the new one. If it fails,
+	/// the previous value is restored (new keys are removed again) and the key is
+	/// reported in `ScanResult::rolled_back`. An override or rollback made while
+	/// the hook ran stays in place.
+	pub fn on_apply<F, Fut, E>(mut self, f: F) -> Self
+	where
+		F: Fn(Option<Arc<T>>, Arc<T>) -> Fut + Send + Sync + 'static,
+		Fut: Future<Output = Result<(), E>> + Send + 'static,
+		E: std::fmt::Display,
+	{
+		self.on_apply = Some(apply_fn(f));
+		self
+	}
+
+	/// Sets how many previous values are kept per key for `history()` and
+	/// `rollback_to()`.
+	///
+	/// Defaults to 10. Zero disables the history.
+	pub fn history_limit(mut self, limit: usize) -> Self {
+		self.history_limit = limit;
+		self
+	}
+
+	/// Keeps the last successfully loaded value of every entry as `<key>.<ext>`
+	/// in `dir`, in the format of the entry's file.
+	///
+	/// Entries that fail in `load()` (or all cached entries, if the directory is
+	/// missing or unreadable) fall back to their cached value and are listed in
+	/// `ScanResult::cached`. The cache holds merged documents, including
+	/// environment overrides and interpolated values.
+	pub fn last_good(mut self, dir: impl Into<PathBuf>) -> Self {
+		self.last_good = Some(LastGood::new(dir.into()));
+		self
+	}
+
+	/// Sets the format of files created by `upsert`.
+	///
+	/// Without it, `upsert` can only rewrite entries that already have a file.
+	pub fn write_format(mut self, format: AnyFormat) -> Self {
+		self.write_format = Some(format);
+		self
+	}
+
+	/// Reloads everything every `interval` while watching, in addition to
+	/// reacting to events, as a safety net for filesystems that lose them (e.g.
+	/// overlay or network mounts). Results are reported like those of any other
+	/// reload.
+	#[cfg(feature = "signal")]
+	pub fn rescan_interval(mut self, interval: Duration) -> Self {
+		self.rescan_interval = Some(interval);
+		self
+	}
+
+	/// Delays each periodic rescan by a random amount of up to `jitter`, so
+	/// controllers started together do not rescan in lockstep.
+	#[cfg(feature = "signal")]
+	pub fn rescan_jitter(mut self, jitter: Duration) -> Self {
+		self.rescan_jitter = jitter;
+		self
+	}
+
+	/// Detects changes by polling every `interval` instead of through filesystem
+	/// events, for filesystems where these never arrive (e.g. network mounts).
+	///
+	/// Files are compared by modification time, size and content. Without this,
+	/// polling is only used for targets whose watcher cannot be created (e.g.
+	/// at the inotify watch limit), which is reported through `on_error`.
+	#[cfg(feature = "signal")]
+	pub fn poll_interval(mut self, interval: Duration) -> Self {
+		self.poll_interval = Some(interval);
+		self
+	}
+
 	pub fn on_error<F>(mut self, f: F) -> Self
 	where
 		F: Fn(LiveError) + Send + Sync + 'static,
@@ -153,17 +399,60 @@ where
 		let path = self
 			.path
 			.ok_or_else(|| LiveError::Builder("path is required".to_string()))?;
+		#[cfg(feature = "signal")]
+		let rescan = match self.rescan_interval {
+			Some(interval) if interval.is_zero() => {
+				return Err(LiveError::Builder(
+					"rescan interval must not be zero".to_string(),
+				));
+			}
+			interval => interval.map(|interval| Rescan::new(interval, self.rescan_jitter)),
+		};
+		#[cfg(feature = "signal")]
+		if self
+			.poll_interval
+			.is_some_and(|interval| interval.is_zero())
+		{
+			return Err(LiveError::Builder(
+				"poll interval must not be zero".to_string(),
+			));
+		}
 
 		Ok(LiveDir {
-			store,
-			loader,
-			path,
-			pattern: self.pattern,
-			scan_mode: self.scan_mode,
-			policy: self.policy,
-			max_entries: self.max_entries,
-			owned_keys: Arc::new(RwLock::new(HashSet::new())),
-			on_error: self.on_error,
+			inner: Arc::new(DirInner {
+				store,
+				loader,
+				path,
+				pattern: self.pattern,
+				scan_mode: self.scan_mode,
+				policy: self.policy,
+				commit_mode: self.commit_mode,
+				max_entries: self.max_entries,
+				concurrency: self.concurrency,
+				env: self.env,
+				defaults_file: self.defaults_file,
+				inheritance: self.inheritance,
+				interpolate: self.interpolate,
+				equal: self.equal,
+				validate_all: self.validate_all,
+				on_apply: self.on_apply,
+				history: Mutex::new(History::new(self.history_limit)),
+				last_good: self.last_good,
+				write_format: self.write_format,
+				overrides: Arc::new(Overrides::new()),
+				writes: Writes::default(),
+				reloads: Reloads::new(),
+				owned_keys: RwLock::new(HashSet::new()),
+				entries: Mutex::new(HashMap::new()),
+				defaults_includes: Mutex::new(Vec::new()),
+				raw: Mutex::new(RawCache::default()),
+				rescan: AtomicBool::new(false),
+				on_error: self.on_error,
+				#[cfg(feature = "signal")]
+				rescan_timer: rescan,
+				#[cfg(feature = "signal")]
+				poll_interval: self.poll_interval,
+			}),
 			#[cfg(feature = "signal")]
 			watch_state: None,
 		})
@@ -192,7 +481,6 @@ impl<T> LiveDir<T> {
 			if let Some(state) = self.watch_state.take()
 				&& let Ok(state) = Arc::try_unwrap(state)
 			{
-				state.watcher.stop();
 				state.abort_handle.abort();
 			}
 		}
@@ -203,6 +491,14 @@ impl<T> LiveDir<T> {
 	pub fn is_watching(&self) -> bool {
 		self.watch_state.is_some()
 	}
+
+	/// Returns the health of the watcher, or `None` if not watching.
+	///
+	/// Transitions are also reported through `on_error`.
+	#[cfg(feature = "signal")]
+	pub fn watch_status(&self) -> Option<WatchStatus> {
+		self.watch_state.as_ref().map(|state| state.status())
+	}
 }
 
 impl<T> LiveDir<T>
@@ -212,15 +508,40 @@ where
 	/// Creates a new LiveDir with required parameters.
 	pub fn new(store: Arc<Store<T>>, loader: DynLoader, path: impl Into<PathBuf>) -> Self {
 		Self {
-			store,
-			loader: Arc::new(loader),
-			path: path.into(),
-			pattern: KeyPattern::default(),
-			scan_mode: ScanMode::default(),
-			policy: UnloadPolicy::default(),
-			max_entries: None,
-			owned_keys: Arc::new(RwLock::new(HashSet::new())),
-			on_error: None,
+			inner: Arc::new(DirInner {
+				store,
+				loader: Arc::new(loader),
+				path: path.into(),
+				pattern: KeyPattern::default(),
+				scan_mode: ScanMode::default(),
+				policy: UnloadPolicy::default(),
+				commit_mode: CommitMode::default(),
+				max_entries: None,
+				concurrency: DEFAULT_CONCURRENCY,
+				env: None,
+				defaults_file: None,
+				inheritance: None,
+				interpolate: false,
+				equal: None,
+				validate_all: None,
+				on_apply: None,
+				history: Mutex::new(History::new(DEFAULT_HISTORY_LIMIT)),
+				last_good: None,
+				write_format: None,
+				overrides: Arc::new(Overrides::new()),
+				writes: Writes::default(),
+				reloads: Reloads::new(),
+				owned_keys: RwLock::new(HashSet::new()),
+				entries: Mutex::new(HashMap::new()),
+				defaults_includes: Mutex::new(Vec::new()),
+				raw: Mutex::new(RawCache::default()),
+				rescan: AtomicBool::new(false),
+				on_error: None,
+				#[cfg(feature = "signal")]
+				rescan_timer: None,
+				#[cfg(feature = "signal")]
+				poll_interval: None,
+			}),
 			#[cfg(feature = "signal")]
 			watch_state: None,
 		}
@@ -231,24 +552,111 @@ where
 	}
 
 	/// Performs an initial scan and load of all configurations in the directory.
+	///
+	/// With `last_good` configured, entries that cannot be loaded fall back to
+	/// their cached value.
 	pub async fn load(&self) -> Result<ScanResult, LiveError> {
-		self.scan_directory().await
+		let scanned = self.inner.scan().await.map(|(result, _)| result);
+		self.inner.load_cached(scanned).await
 	}
 
 	/// Manually reloads all configurations by rescanning the directory.
+	///
+	/// Reloads run one at a time, including the ones triggered by watching.
+	/// Calls made while one runs share a single follow-up rescan, whose result
+	/// they all return.
 	pub async fn reload(&self) -> Result<ScanResult, LiveError> {
-		self.scan_directory().await
+		self.inner.scan().await.map(|(result, _)| result)
+	}
+
+	/// Returns whether a reload is running or waiting to run.
+	pub fn reload_state(&self) -> ReloadState {
+		self.inner.reloads.state()
 	}
 
 	/// Gets a configuration by key.
 	pub fn get(&self, key: &str) -> Option<Arc<T>> {
-		self.store.get(key)
+		self.inner.store.get(key)
+	}
+
+	/// Returns the latest generation, or 0 before anything was loaded.
+	///
+	/// Every scan that adds or updates entries (and every rollback) starts a new
+	/// generation shared by all entries it changed.
+	pub fn current_generation(&self) -> u64 {
+		self.inner.history().current()
+	}
+
+	/// Returns the recorded previous and current values of `key`, oldest first.
+	pub fn history(&self, key: &str) -> Vec<Revision<T>> {
+		self.inner.history().list(key)
+	}
+
+	/// Restores every entry to the value it held at `generation`, without
+	/// touching files.
+	///
+	/// Entries that did not exist yet or were removed since are left untouched.
+	/// Restored values become a new generation and stay in place until their
+	/// files change. Overridden entries are skipped. `on_apply` is not run.
+	/// Returns the restored keys.
+	pub async fn rollback_to(&self, generation: u64) -> Result<Vec<String>, LiveError> {
+		let inner = &self.inner;
+		let owned = inner.owned_keys.read().await;
+		inner
+			.history()
+			.rollback(&inner.store, owned.iter(), generation, |key| {
+				inner.overrides.contains(key)
+			})
+	}
+
+	/// Pins `value` for `key` in the store above whatever its file says.
+	///
+	/// The value is validated first. While it is in place, scans still load and
+	/// validate the file but hold its value back (see `ScanResult::overridden`)
+	/// until the override is cleared. `on_apply` is not run and no generation
+	/// is recorded. Keys without a file may be overridden as well; they are
+	/// removed again when the override ends.
+	pub fn set_override(&self, key: &str, value: T) -> Result<(), LiveError> {
+		let inner = &self.inner;
+		let value = inner.validate(key, value)?;
+		inner.overrides.set(&inner.store, key, value, None);
+		Ok(())
+	}
+
+	/// Like [`set_override`](Self::set_override), but restores the file value
+	/// after `ttl`.
+	///
+	/// Must be called within a Tokio runtime.
+	pub fn set_override_for(&self, key: &str, value: T, ttl: Duration) -> Result<(), LiveError> {
+		let inner = &self.inner;
+		let value = inner.validate(key, value)?;
+		inner.overrides.set_for(&inner.store, key, value, ttl);
+		Ok(())
+	}
+
+	/// Returns every active override, ordered by key.
+	pub fn overrides(&self) -> Vec<Override<T>> {
+		self.inner.overrides.list()
+	}
+
+	/// Ends the override of `key` and publishes the latest file value again.
+	///
+	/// Returns false if `key` was not overridden.
+	pub fn clear_override(&self, key: &str) -> bool {
+		let inner = &self.inner;
+		inner.overrides.clear(&inner.store, key)
+	}
+
+	/// Ends every override, returning the cleared keys.
+	pub fn clear_overrides(&self) -> Vec<String> {
+		let inner = &self.inner;
+		inner.overrides.clear_all(&inner.store)
 	}
 
 	/// Returns a snapshot of all configurations managed by this LiveDir.
 	pub async fn snapshot(&self) -> HashMap<String, Arc<T>> {
-		let owned = self.owned_keys.read().await;
-		let store_snapshot = self.store.snapshot();
+		let owned = self.inner.owned_keys.read().await;
+		let store_snapshot = self.inner.store.snapshot();
 		store_snapshot
 			.iter()
 			.filter(|(k, _)| owned.contains(*k))
@@ -258,142 +666,291 @@ where
 
 	/// Returns all keys managed by this LiveDir.
 	pub async fn keys(&self) -> Vec<String> {
-		let owned = self.owned_keys.read().await;
+		let owned = self.inner.owned_keys.read().await;
 		owned.iter().cloned().collect()
 	}
 
 	/// Returns the number of configurations managed by this LiveDir.
 	pub async fn len(&self) -> usize {
-		self.owned_keys.read().await.len()
+		self.inner.owned_keys.read().await.len()
 	}
 
 	/// Returns true if no configurations are loaded by this LiveDir.
 	pub async fn is_empty(&self) -> bool {
-		self.owned_keys.read().await.is_empty()
+		self.inner.owned_keys.read().await.is_empty()
+	}
+
+	/// Writes `value` to the file of `key`, creating the file if needed, and
+	/// reloads the entry.
+	///
+	/// The value is validated and compared to the current one. Only the fields
+	/// that changed are set in the entry file's own document, so values from
+	/// defaults, parents or the environment, unresolved `${...}` references
+	/// and `extends` or `$include` keys stay as they are. New entries get the
+	/// whole value. The file is serialized in its own format (or `write_format`
+	/// for new entries) and written atomically (temp file and rename) within the
+	/// directory. The watch event caused by the write does not trigger another
+	/// reload.
+	///
+	/// Returns the result of reloading `key`; failures such as a rejection by
+	/// `validate_all` are listed in it.
+	pub async fn upsert(&self, key: &str, value: T) -> Result<ScanResult, LiveError>
+	where
+		T: Serialize,
+	{
+		let inner = &self.inner;
+		check_key(key)?;
+		let _guard = inner.writes.lock().await;
+		let value = inner.validate(key, value)?;
+		let path = match inner.entry_file(key).await? {
+			Some(path) => path,
+			None => inner.new_entry_file(key).await?,
+		};
+		check_sandbox(&inner.path, &path).await?;
+		let to_value = |value: &T| {
+			serde_json::to_value(value).map_err(|e| LiveError::Write(format!("{}: {}", key, e)))
+		};
+		// Only changed fields go into the entry's own document, like `Live::update`
+		let document = match inner.overrides.file_entry(&inner.store, key) {
+			Some(current) if current.meta.source == path => {
+				let mut document = deserialize(&fs::read(&path).await?, &path)?;
+				patch(
+					&mut document,
+					&to_value(&current.value)?,
+					&to_value(&value)?,
+				);
+				document
+			}
+			_ => to_value(&value)?,
+		};
+		let bytes = serialize(&document, &path)?;
+		inner.writes.write(&path, &bytes).await?;
+		inner
+			.scan_keys(&HashSet::from([key.to_string()]))
+			.await
+			.map(|(result, _)| result)
+	}
+
+	/// Changes the single field at `path` (e.g. `db.port`) in the file of `key`,
+	/// keeping its comments and layout, and reloads the entry.
+	///
+	/// See [`Live::set_field`](super::Live::set_field) for what can be edited.
+	/// If the entry fails to load or apply afterwards, the previous content is
+	/// written back and the edit fails with `LiveError::Rejected`.
+	pub async fn set_field<V>(&self, key: &str, path: &str, value: V) -> Result<ScanResult, LiveError>
+	where
+		V: Serialize,
+	{
+		let inner = &self.inner;
+		check_key(key)?;
+		let _guard = inner.writes.lock().await;
+		let file = inner
+			.entry_file(key)
+			.await?
+			.ok_or_else(|| LiveError::Write(format!("{} has no file", key)))?;
+		check_sandbox(&inner.path, &file).await?;
+		let value =
+			serde_json::to_value(value).map_err(|e| LiveError::Write(format!("{}: {}", path, e)))?;
+		let original = fs::read_to_string(&file).await?;
+		let edited = edit::set_field(&original, &file, path, &value)?;
+		inner.writes.write(&file, edited.as_bytes()).await?;
+		let (result, _) = inner.scan_keys(&HashSet::from([key.to_string()])).await?;
+		let rejected = result
+			.failed
+			.iter()
+			.chain(&result.rolled_back)
+			.find(|(k, _)| k == key);
+		if let Some((_, reason)) = rejected {
+			// Failed entries keep their current value
+			inner.writes.write(&file, original.as_bytes()).await?;
+			return Err(LiveError::Rejected(format!("[{}] {}", key, reason)));
+		}
+		Ok(result)
+	}
+
+	/// Deletes the file of `key` and reloads the entry, which removes it.
+	///
+	/// In `ScanMode::Subdirs` only the config file is deleted, not its directory.
+	pub async fn delete(&self, key: &str) -> Result<ScanResult, LiveError> {
+		let inner = &self.inner;
+		check_key(key)?;
+		let _guard = inner.writes.lock().await;
+		let path = inner
+			.entry_file(key)
+			.await?
+			.ok_or_else(|| LiveError::Write(format!("{} has no file", key)))?;
+		check_sandbox(&inner.path, &path).await?;
+		inner.writes.delete(&path).await?;
+		inner
+			.scan_keys(&HashSet::from([key.to_string()]))
+			.await
+			.map(|(result, _)| result)
 	}
 
 	/// Subscribes to store change events.
 	#[cfg(feature = "events")]
 	pub fn subscribe(&self) -> tokio::sync::broadcast::Receiver<atomhold::HoldEvent<T>> {
-		self.store.subscribe()
+		self.inner.store.subscribe()
 	}
 
 	/// Attaches a filesystem watcher for live reloading (borrowing version).
 	///
-	/// Must call `load()` before `start_watching()` to ensure the directory exists.
+	/// Watching may start before the directory exists, in which case it is
+	/// loaded as soon as it appears.
 	#[cfg(feature = "signal")]
 	pub async fn start_watching(&mut self, config: WatcherConfig) -> Result<(), LiveError> {
-		let watch_path = fs::canonicalize(&self.path).await.map_err(LiveError::Io)?;
-
-		let target = Target::Directory(watch_path);
-		let watcher = Watcher::new(target, config)?;
-
-		let mut rx = watcher.subscribe();
-		let store = self.store.clone();
-		let loader = self.loader.clone();
-		let path = self.path.clone();
-		let pattern = self.pattern.clone();
-		let scan_mode = self.scan_mode.clone();
-		let policy = self.policy;
-		let max_entries = self.max_entries;
-		let owned_keys = self.owned_keys.clone();
-		let on_error = self.on_error.clone();
+		let mut watch_path = fs::canonicalize(&self.inner.path).await.ok();
+
+		let mut watch_set = WatchSet::new(config, self.inner.rescan_timer, self.inner.poll_interval);
+		watch_set.sync(self.inner.watch_targets(watch_path.as_deref()))?;
+		let status = watch_set.status();
+
+		let inner = self.inner.clone();
 
 		let handle = tokio::spawn(async move {
-			while let Ok(_event) = rx.recv().await {
-				// On any change, rescan the entire directory
-
-				match Self::do_scan(
-					&store,
-					&loader,
-					&path,
-					&pattern,
-					&scan_mode,
-					policy,
-					max_entries,
-					&owned_keys,
-				)
-				.await
+			loop {
+				// Until the directory exists, every event is a step towards it
+				let Some(dir) = watch_path.clone() else {
+					watch_path = inner.find_dir(&mut watch_set).await;
+					if watch_path.is_none()
+						&& let Some(e) = watch_set.next().await.error()
+						&& let Some(ref cb) = inner.on_error
+					{
+						cb(e);
+					}
+					continue;
+				};
+
+				let mut paths = Vec::new();
+				// Dropped events may have touched any entry
+				let mut overflow = false;
+				let change = watch_set.next().await;
+				if let Some(e) = change.error()
+					&& let Some(ref cb) = inner.on_error
 				{
-					Ok(result) => {
-						if let Some(ref cb) = on_error {
-							for (key, err) in result.failed {
-								let msg = format!("[{}] {}", key, err);
-								cb(LiveError::Load(FmtError::ParseError(msg)));
-							}
+					cb(e);
+				}
+				match change {
+					Change::Events(events) => paths.extend(events),
+					Change::Lagged(_) | Change::Restored(_) | Change::Rescan => overflow = true,
+					Change::Failed(_) | Change::Fallback(_) => continue,
+				}
+				// Handle events that queued up in the meantime in one go
+				loop {
+					match watch_set.try_recv() {
+						Ok(Some(event)) => paths.extend(event.paths),
+						Ok(None) => break,
+						Err(_) => {
+							overflow = true;
+							break;
+						}
+					}
+				}
+
+				// Writes made by `upsert` and `delete` are loaded already
+				let mut outside = Vec::with_capacity(paths.len());
+				for path in paths {
+					if !inner.writes.is_own(&path).await {
+						outside.push(path);
+					}
+				}
+				let paths = outside;
+
+				// Reload only the affected keys, or everything if that is unclear
+				let keys = if overflow {
+					None
+				} else {
+					inner.affected_keys(&paths, &dir)
+				};
+				let scanned = match keys {
+					Some(keys) if keys.is_empty() => continue,
+					Some(keys) => inner.scan_keys(&keys).await,
+					None => inner.scan().await,
+				};
+				match scanned {
+					Ok((_, errors)) => {
+						if let Some(ref cb) = inner.on_error {
+							errors.into_iter().for_each(|e| cb(e));
+						}
+						// Included and interpolated files may have changed
+						if let Err(e) = watch_set.sync(inner.watch_targets(Some(&dir)))
+							&& let Some(ref cb) = inner.on_error
+						{
+							cb(LiveError::Signal(e));
 						}
 					}
 					Err(e) => {
-						if let Some(ref cb) = on_error {
+						if let Some(ref cb) = inner.on_error {
 							cb(e);
 						}
 					}
 				}
-
-				// Errors during watch are silently ignored (except via callback).
-
-				// Use events feature to observe failures if needed.
 			}
 		});
 
 		self.watch_state = Some(Arc::new(WatchState {
-			watcher,
 			abort_handle: handle.abort_handle(),
+			status,
 		}));
 		Ok(())
 	}
 
 	/// Attaches a filesystem watcher for live reloading (consuming version).
-	///
-	/// Must call `load()` before `watch()` to ensure the directory exists.
 	#[cfg(feature = "signal")]
 	pub async fn watch(mut self, config: WatcherConfig) -> Result<Self, LiveError> {
 		self.start_watching(config).await?;
 		Ok(self)
 	}
+}
 
-	/// Internal: Scan the directory and sync with store.
-	async fn scan_directory(&self) -> Result<ScanResult, LiveError> {
-		Self::do_scan(
-			&self.store,
-			&self.loader,
-			&self.path,
-			&self.pattern,
-			&self.scan_mode,
-			self.policy,
-			self.max_entries,
-			&self.owned_keys,
-		)
-		.await
-	}
-
-	/// Static method to perform scan (used by both load and watch).
-	#[allow(clippy::too_many_arguments)]
-	async fn do_scan(
-		store: &Arc<Store<T>>,
-		loader: &Arc<DynLoader>,
-		path: &std::path::Path,
-		pattern: &KeyPattern,
-		scan_mode: &ScanMode,
-		policy: UnloadPolicy,
-		max_entries: Option<usize>,
-		owned_keys: &Arc<RwLock<HashSet<String>>>,
-	) -> Result<ScanResult, LiveError> {
+impl<T> DirInner<T>
+where
+	T: Clone + Send + Sync + DeserializeOwned + PreProcess + ValidateConfig + 'static,
+{
+	/// Scans the directory and syncs with the store (used by both load and watch).
+	///
+	/// Alongside the result, returns the error behind every failed key.
+	async fn scan(&self) -> Result<(ScanResult, Vec<LiveError>), LiveError> {
+		self.reloads.run(true, self.scan_with(None)).await
+	}
+
+	/// Reloads only `keys` (and, with inheritance, their dependents).
+	///
+	/// Other entries keep their current value and are left out of the result.
+	async fn scan_keys(
+		&self,
+		keys: &HashSet<String>,
+	) -> Result<(ScanResult, Vec<LiveError>), LiveError> {
+		let only = (!self.rescan.load(Ordering::Relaxed)).then_some(keys);
+		self.reloads.run(only.is_none(), self.scan_with(only)).await
+	}
+
+	/// Scans the directory, reloading either every entry or only the `only` keys.
+	async fn scan_with(
+		&self,
+		only: Option<&HashSet<String>>,
+	) -> Result<(ScanResult, Vec<LiveError>), LiveError> {
+		let store = &self.store;
+		let path = &self.path;
 		let mut result = ScanResult::default();
+		if only.is_none() {
+			self.rescan.store(false, Ordering::Relaxed);
+		}
 
 		// Check if directory exists
 		if !tokio::fs::try_exists(path).await.unwrap_or(false) {
-			return Ok(result);
+			return Ok((result, Vec::new()));
 		}
 
-		// Collect all valid entries from filesystem
-		// Store (key, load_name)
+		// Entries on disk, by key, with the name each is loaded by
 		let mut fs_entries: HashMap<String, String> = HashMap::new();
+		// Inheritance templates, never exposed as keys
+		let mut templates: HashMap<String, String> = HashMap::new();
 
 		let mut entries = fs::read_dir(path).await?;
 		while let Some(entry) = entries.next_entry().await? {
 			// Check max_entries limit
-			if let Some(max) = max_entries
+			if let Some(max) = self.max_entries
 				&& fs_entries.len() >= max
 			{
 				return Err(LiveError::LimitExceeded(format!(
@@ -411,100 +968,1031 @@ where
 				continue;
 			}
 
-			match scan_mode {
+			let (target, key_name) = match self
+				.inheritance
+				.as_ref()
+				.and_then(|i| i.template_name(&name))
+			{
+				Some(rest) => (&mut templates, rest),
+				None => (&mut fs_entries, name.as_ref()),
+			};
+
+			match &self.scan_mode {
 				ScanMode::Files => {
 					if file_type.is_file()
-						&& let Some(key) = pattern.extract(&name)
+						&& !self.is_defaults_file(&name)
+						&& let Some(key) = self.pattern.extract(key_name)
 					{
 						// Full filename with extension
-						fs_entries.insert(key, name.to_string());
+						target.insert(key, name.to_string());
 					}
 				}
 				ScanMode::Subdirs { config_file } => {
 					if file_type.is_dir()
-						&& let Some(key) = pattern.extract(&name)
+						&& let Some(key) = self.pattern.extract(key_name)
 					{
 						// Base name without extension, let loader.load() probe
 						let base_name = format!("{}/{}", name, config_file);
-						fs_entries.insert(key, base_name);
+						target.insert(key, base_name);
 					}
 				}
 			}
 		}
 
-		// Track which keys are currently valid in the filesystem
-		let mut fs_keys: HashSet<String> = HashSet::new();
-
-		// Load all configs and insert/update in store
-		for (key, load_name) in &fs_entries {
-			let is_new = store.get(key).is_none();
+		// A changed parent changes every entry inheriting from it
+		let only = only.map(|keys| self.with_dependents(keys));
+		let selected = |key: &String| only.as_ref().is_none_or(|keys| keys.contains(key));
 
-			// Files mode: load_file (exact path)
-			// Subdirs mode: load (probe extensions)
-			let load_result = match scan_mode {
-				ScanMode::Files => loader.load_file::<T>(load_name).await,
-				ScanMode::Subdirs { .. } => loader.load::<T>(load_name).await,
-			};
+		// Shared defaults are loaded once per scan. If they are invalid, every
+		// entry fails and keeps its old value.
+		let defaults = self.load_defaults().await;
+		// Untyped documents are only required when something is merged into them
+		let allow_typed = self.env.is_none()
+			&& self.inheritance.is_none()
+			&& !self.interpolate
+			&& matches!(defaults, Ok(None));
 
-			match load_result {
-				LoadResult::Ok { mut value, info } => {
-					value.set_context(key);
-					if let Err(e) = value.validate_config() {
-						// Validation failed - keep old value if available
-						if store.get(key).is_some() {
-							fs_keys.insert(key.clone());
+		// Load every entry and template (concurrently, see `load_all`) before
+		// resolving inheritance, which may reference any of them
+		let mut raw = Vec::new();
+		let mut cache = RawCache::default();
+		if self.inheritance.is_some() && only.is_some() {
+			// Entries that are not reloaded resolve against their cached documents
+			let present = |key: &String| fs_entries.contains_key(key) || templates.contains_key(key);
+			let previous = self.raw.lock().unwrap_or_else(|e| e.into_inner());
+			cache.documents = previous
+				.documents
+				.iter()
+				.filter(|(key, _)| !selected(key) && present(key))
+				.map(|(key, document)| (key.clone(), document.clone()))
+				.collect();
+			cache.template_files = previous
+				.template_files
+				.iter()
+				.filter(|(key, _)| !selected(key) && present(key))
+				.map(|(key, files)| (key.clone(), files.clone()))
+				.collect();
+		}
+		let documents = &mut cache.documents;
+		if defaults.is_ok() {
+			if self.inheritance.is_some() {
+				for (key, loaded) in self.load_all(&templates, &selected, false).await {
+					let document = match loaded {
+						Ok(Some(Loaded::Document(document, _, files))) => {
+							cache.template_files.insert(key.clone(), files);
+							Ok(document)
+						}
+						Ok(_) => continue,
+						Err(e) => Err(e.to_string()),
+					};
+					documents.insert(key.clone(), document);
+				}
+			}
+			for (key, loaded) in self.load_all(&fs_entries, &selected, allow_typed).await {
+				if self.inheritance.is_some() {
+					match &loaded {
+						Ok(Some(Loaded::Document(document, _, _))) => {
+							documents.insert(key.clone(), Ok(document.clone()));
+						}
+						Err(e) => {
+							documents.insert(key.clone(), Err(e.to_string()));
 						}
-						result.failed.push((key.clone(), e.to_string()));
-						continue;
+						_ => {}
 					}
-					let source_path = fs::canonicalize(&info.path).await.unwrap_or(info.path);
-					store.insert(key.clone(), value, source_path, policy);
+				}
+				raw.push((key, loaded));
+			}
+		}
+
+		// Track which keys are currently valid in the filesystem. Keys that are
+		// not reloaded stay as they are.
+		let mut fs_keys: HashSet<String> = match &only {
+			Some(keys) => self
+				.owned_keys
+				.read()
+				.await
+				.iter()
+				.filter(|key| !keys.contains(*key))
+				.cloned()
+				.collect(),
+			None => HashSet::new(),
+		};
+		let mut errors = Vec::new();
+		let mut states = HashMap::new();
+		// New and changed values, written to the store once every entry is decoded
+		let mut staged = Vec::new();
+
+		let failed_defaults = match &defaults {
+			Ok(_) => Vec::new(),
+			Err(e) => fs_entries
+				.keys()
+				.filter(|key| selected(key))
+				.map(|key| (key, Err(LiveError::Load(FmtError::ParseError(e.clone())))))
+				.collect(),
+		};
+
+		// Resolve, decode and stage
+		for (key, loaded) in raw.into_iter().chain(failed_defaults) {
+			let is_new = self.overrides.file_entry(store, key).is_none();
+
+			let loaded = match loaded {
+				Ok(Some(Loaded::Document(document, source_path, included))) => {
+					let defaults = defaults.as_ref().ok().and_then(Option::as_ref);
+					self
+						.finish_entry(key, &fs_entries[key], document, defaults, documents)
+						.await
+						.map(|(value, mut state)| {
+							state.files.extend(included);
+							Some((value, source_path, state))
+						})
+				}
+				Ok(Some(Loaded::Typed(value, source_path))) => {
+					Ok(Some((value, source_path, EntryState::default())))
+				}
+				Ok(None) => Ok(None),
+				Err(e) => Err(e),
+			};
+
+			match loaded {
+				Ok(Some((value, source_path, state))) => {
 					fs_keys.insert(key.clone());
 
 					if is_new {
+						staged.push((key.clone(), value, source_path));
 						result.added.push(key.clone());
-					} else {
+					} else if let Some(diff) = self.changes(key, &value, &source_path, &state) {
+						staged.push((key.clone(), value, source_path));
 						result.updated.push(key.clone());
+						result.diffs.push((key.clone(), diff));
+					} else {
+						// Skip no-op reloads so subscribers only see real changes
+						result.unchanged.push(key.clone());
 					}
+					states.insert(key.clone(), state);
+				}
+				Ok(None) => {
+					// File does not exist (Subdirs mode where subdir exists but config file missing)
+					// Skip
 				}
-				LoadResult::Invalid(e) => {
-					// Keep old value if available
+				Err(e) => {
+					// Parsing or validation failed - keep old value if available
 					if store.get(key).is_some() {
 						fs_keys.insert(key.clone());
 					}
-					result.failed.push((key.clone(), e.to_string()));
-				}
-				LoadResult::NotFound => {
-					// File does not exist (Subdirs mode where subdir exists but config file missing)
-					// Skip
+					let (message, error) = match e {
+						LiveError::Load(e) => {
+							let message = e.to_string();
+							let error = FmtError::ParseError(format!("[{}] {}", key, message));
+							(message, LiveError::Load(error))
+						}
+						e => (e.to_string(), e),
+					};
+					result.failed.push((key.clone(), message));
+					errors.push(error);
 				}
 			}
 		}
 
+		// Changes handed to `on_apply` once they are stored
+		let mut applied = Vec::new();
 		// Update owned_keys and remove keys that are no longer in the filesystem
 		{
-			let mut owned = owned_keys.write().await;
-			let old_owned: HashSet<String> = owned.clone();
-
-			let keys_to_check: Vec<_> = old_owned.difference(&fs_keys).cloned().collect();
-			for key in keys_to_check {
-				match store.remove(&key) {
-					Ok(_) => {
-						result.removed.push(key);
-					}
-					Err(_) => {
-						// Persistent policy prevented removal
-						result.retained.push(key.clone());
-						fs_keys.insert(key);
+			let mut owned = self.owned_keys.write().await;
+			let mut removed: Vec<String> = owned.difference(&fs_keys).cloned().collect();
+			self.validate_staged(
+				&mut staged,
+				&mut removed,
+				&mut states,
+				&mut fs_keys,
+				&mut result,
+				&mut errors,
+			);
+			self.hold_overridden(&mut staged, &mut removed, &mut result);
+			if self.on_apply.is_some() {
+				let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+				applied = staged
+					.iter()
+					.map(|(key, _, _)| (key.clone(), store.get_entry(key), entries.get(key).cloned()))
+					.collect();
+			}
+
+			let committed = match self.commit_mode {
+				CommitMode::Partial => {
+					self.commit_partial(staged, removed, &mut fs_keys, &mut result);
+					true
+				}
+				CommitMode::Atomic => self.commit_atomic(staged, removed, &mut fs_keys, &mut result),
+			};
+
+			if committed {
+				// Failed entries keep the state of their last good load
+				let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+				entries.retain(|key, _| fs_keys.contains(key));
+				entries.extend(states);
+
+				*owned = fs_keys;
+			} else {
+				applied.clear();
+			}
+		}
+		self.apply_changes(applied, &mut result, &mut errors).await;
+		self.record(&result);
+		self.save_last_good(&result, &mut errors).await;
+		if self.inheritance.is_some() {
+			*self.raw.lock().unwrap_or_else(|e| e.into_inner()) = cache;
+		}
+
+		result.sort();
+
+		Ok((result, errors))
+	}
+
+	/// Runs the `validate_all` hook over the entries as they will be after the
+	/// scan, and drops the changes it rejects.
+	fn validate_staged(
+		&self,
+		staged: &mut Vec<(String, T, PathBuf)>,
+		removed: &mut Vec<String>,
+		states: &mut HashMap<String, EntryState>,
+		fs_keys: &mut HashSet<String>,
+		result: &mut ScanResult,
+		errors: &mut Vec<LiveError>,
+	) {
+		let Some(validate_all) = &self.validate_all else {
+			return;
+		};
+		let entries: HashMap<String, Arc<T>> = fs_keys
+			.iter()
+			.filter_map(|key| {
+				let value = match staged.iter().find(|(k, _, _)| k == key) {
+					Some((_, value, _)) => Arc::new(value.clone()),
+					None => self.store.get(key)?,
+				};
+				Some((key.clone(), value))
+			})
+			.collect();
+
+		let rejected = match validate_all(&entries) {
+			Ok(()) => return,
+			Err(Rejection::Keys(keys)) => keys,
+			Err(Rejection::All(reason)) => {
+				// Removed keys stay as well
+				let keys: Vec<String> = staged.iter().map(|(key, _, _)| key.clone()).collect();
+				fs_keys.extend(removed.iter().cloned());
+				keys
+					.into_iter()
+					.chain(removed.drain(..))
+					.map(|key| (key, reason.clone()))
+					.collect()
+			}
+		};
+
+		for (key, reason) in rejected {
+			staged.retain(|(k, _, _)| *k != key);
+			// Keep the state of the current value
+			states.remove(&key);
+			if self.store.get(&key).is_none() {
+				fs_keys.remove(&key);
+			}
+			result.added.retain(|k| *k != key);
+			result.updated.retain(|k| *k != key);
+			result.unchanged.retain(|k| *k != key);
+			result.diffs.retain(|(k, _)| *k != key);
+			errors.push(LiveError::Rejected(format!("[{}] {}", key, reason)));
+			result.failed.push((key, reason));
+		}
+		// Rejections depend on other entries, so the next reload looks at all of them
+		self.rescan.store(true, Ordering::Relaxed);
+	}
+
+	/// Holds back the changes of overridden keys instead of publishing them.
+	///
+	/// Nothing is held back from an atomic scan that is going to be rejected.
+	fn hold_overridden(
+		&self,
+		staged: &mut Vec<(String, T, PathBuf)>,
+		removed: &mut Vec<String>,
+		result: &mut ScanResult,
+	) {
+		if self.commit_mode == CommitMode::Atomic && !result.failed.is_empty() {
+			return;
+		}
+		let mut held = Vec::new();
+		*staged = std::mem::take(staged)
+			.into_iter()
+			.filter_map(|(key, value, source)| {
+				match self.overrides.hold(&key, value, source, self.policy) {
+					Some((value, source)) => Some((key, value, source)),
+					None => {
+						held.push(key);
+						None
 					}
 				}
+			})
+			.collect();
+		removed.retain(|key| {
+			// The override outlives the file, but the key is no longer owned
+			if !self.overrides.forget(key) {
+				return true;
 			}
+			held.push(key.clone());
+			false
+		});
+		for key in held {
+			result.added.retain(|k| *k != key);
+			result.updated.retain(|k| *k != key);
+			result.diffs.retain(|(k, _)| *k != key);
+			result.overridden.push(key);
+		}
+	}
 
-			*owned = fs_keys;
+	/// Runs `on_apply` for every stored change and rolls back the ones it rejects.
+	///
+	/// `applied` holds each key with its previous store entry and state.
+	async fn apply_changes(
+		&self,
+		applied: Vec<(String, Option<Entry<T>>, Option<EntryState>)>,
+		result: &mut ScanResult,
+		errors: &mut Vec<LiveError>,
+	) {
+		let Some(on_apply) = &self.on_apply else {
+			return;
+		};
+		let mut rolled_back = Vec::new();
+		for (key, previous, state) in applied {
+			let Some(new) = self.store.get(&key) else {
+				continue;
+			};
+			let old = previous.as_ref().map(|entry| Arc::clone(&entry.value));
+			if let Err(e) = on_apply(old, Arc::clone(&new)).await {
+				let is_new = previous.is_none();
+				self.overrides.restore(&self.store, &key, &new, previous);
+				rolled_back.push((key, is_new, state, e));
+			}
+		}
+		if rolled_back.is_empty() {
+			return;
 		}
 
+		let mut owned = self.owned_keys.write().await;
+		let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+		for (key, is_new, state, message) in rolled_back {
+			// Describe the restored value again, so the next reload retries
+			match state {
+				Some(state) => entries.insert(key.clone(), state),
+				None => entries.remove(&key),
+			};
+			if is_new {
+				owned.remove(&key);
+			}
+			result.added.retain(|k| *k != key);
+			result.updated.retain(|k| *k != key);
+			result.diffs.retain(|(k, _)| *k != key);
+			errors.push(LiveError::RolledBack(format!("[{}] {}", key, message)));
+			result.rolled_back.push((key, message));
+		}
+	}
+
+	/// Falls back to cached values for entries the initial scan could not load.
+	async fn load_cached(
+		&self,
+		scanned: Result<ScanResult, LiveError>,
+	) -> Result<ScanResult, LiveError> {
+		let Some(last_good) = &self.last_good else {
+			return scanned;
+		};
+		let exists = fs::try_exists(&self.path).await.unwrap_or(false);
+		let (mut result, candidates) = match scanned {
+			Ok(result) if exists => {
+				let failed = result.failed.clone();
+				(result, failed)
+			}
+			Ok(result) => {
+				let reason = format!("{} does not exist", self.path.display());
+				let keys = last_good.keys().await.unwrap_or_default();
+				(
+					result,
+					keys.into_iter().map(|key| (key, reason.clone())).collect(),
+				)
+			}
+			Err(e) => {
+				let Ok(keys) = last_good.keys().await else {
+					return Err(e);
+				};
+				let reason = e.to_string();
+				let candidates: Vec<_> = keys.into_iter().map(|key| (key, reason.clone())).collect();
+				if candidates.is_empty() {
+					return Err(e);
+				}
+				(ScanResult::default(), candidates)
+			}
+		};
+
+		let mut applied = Vec::new();
+		for (key, reason) in candidates {
+			// Entries that kept an older value stay as they are
+			if self.store.get(&key).is_some() {
+				continue;
+			}
+			if let Ok(Some((value, path))) = last_good.load::<T>(&key).await {
+				self.store.insert(key.clone(), value, path, self.policy);
+				self.owned_keys.write().await.insert(key.clone());
+				applied.push((key.clone(), None, None));
+				result.cached.push((key, reason));
+			}
+		}
+		self
+			.apply_changes(applied, &mut result, &mut Vec::new())
+			.await;
+		let rolled_back = &result.rolled_back;
+		result
+			.cached
+			.retain(|(key, _)| !rolled_back.iter().any(|(k, _)| k == key));
 		Ok(result)
 	}
+
+	/// Writes the documents of added and updated keys to the last-known-good cache
+	/// and drops removed keys from it.
+	async fn save_last_good(&self, result: &ScanResult, errors: &mut Vec<LiveError>) {
+		let Some(last_good) = &self.last_good else {
+			return;
+		};
+		let documents: Vec<(String, Value, PathBuf)> = {
+			let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+			result
+				.added
+				.iter()
+				.chain(&result.updated)
+				.filter_map(|key| {
+					let document = entries.get(key)?.document.clone()?;
+					let source = self.overrides.file_entry(&self.store, key)?.meta.source;
+					Some((key.clone(), document, source))
+				})
+				.collect()
+		};
+		for (key, document, source) in documents {
+			if let Err(e) = last_good.save(&key, &document, &source).await {
+				errors.push(e);
+			}
+		}
+		for key in &result.removed {
+			if let Err(e) = last_good.remove(key).await {
+				errors.push(e);
+			}
+		}
+	}
+
+	/// Records the values of added and updated keys as a new generation.
+	fn record(&self, result: &ScanResult) {
+		if result.added.is_empty() && result.updated.is_empty() {
+			return;
+		}
+		let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+		let mut history = self.history();
+		let generation = history.advance();
+		for key in result.added.iter().chain(&result.updated) {
+			if let Some(current) = self.store.get_entry(key) {
+				let hash = entries.get(key).and_then(|state| state.hash);
+				history.record(key, generation, current.value, current.meta.source, hash);
+			}
+		}
+	}
+
+	fn history(&self) -> std::sync::MutexGuard<'_, History<T>> {
+		self.history.lock().unwrap_or_else(|e| e.into_inner())
+	}
+
+	fn validate(&self, key: &str, mut value: T) -> Result<T, LiveError> {
+		value.set_context(key);
+		value.validate_config()?;
+		Ok(value)
+	}
+
+	/// Writes staged values to the store one by one and removes stale keys.
+	fn commit_partial(
+		&self,
+		staged: Vec<(String, T, PathBuf)>,
+		removed: Vec<String>,
+		fs_keys: &mut HashSet<String>,
+		result: &mut ScanResult,
+	) {
+		for (key, value, source) in staged {
+			self.store.insert(key, value, source, self.policy);
+		}
+		for key in removed {
+			match self.store.remove(&key) {
+				Ok(_) => {
+					result.removed.push(key);
+				}
+				Err(_) => {
+					// Persistent policy prevented removal
+					result.retained.push(key.clone());
+					fs_keys.insert(key);
+				}
+			}
+		}
+	}
+
+	/// Publishes staged values and removals as a single store update, or nothing
+	/// at all if any entry failed.
+	///
+	/// A single change is written on its own, leaving every other entry as it
+	/// is. The store's only multi-key update is `replace_all`, so several
+	/// changes publish the other entries again as well, with new `Arc`s.
+	///
+	/// Returns false if the scan was rejected.
+	fn commit_atomic(
+		&self,
+		staged: Vec<(String, T, PathBuf)>,
+		removed: Vec<String>,
+		fs_keys: &mut HashSet<String>,
+		result: &mut ScanResult,
+	) -> bool {
+		if !result.failed.is_empty() {
+			let mut failed: Vec<&str> = result.failed.iter().map(|(key, _)| key.as_str()).collect();
+			failed.sort();
+			let reason = format!("rejected: {} failed", failed.join(", "));
+			let rejected: Vec<String> = result
+				.added
+				.drain(..)
+				.chain(result.updated.drain(..))
+				.collect();
+			result
+				.failed
+				.extend(rejected.into_iter().map(|key| (key, reason.clone())));
+			result.diffs.clear();
+			// Keys that were not reloaded may carry rejected changes as well
+			self.rescan.store(true, Ordering::Relaxed);
+			return false;
+		}
+		if staged.len() + removed.len() <= 1 {
+			self.commit_partial(staged, removed, fs_keys, result);
+			return true;
+		}
+
+		let current = self.store.snapshot();
+		let mut entries: HashMap<String, (T, PathBuf, UnloadPolicy)> = HashMap::new();
+		for key in removed {
+			// `replace_all` keeps persistent entries that are left out
+			match current.get(&key) {
+				Some(entry) if entry.meta.policy == UnloadPolicy::Persistent => {
+					result.retained.push(key.clone());
+					fs_keys.insert(key);
+				}
+				_ => result.removed.push(key),
+			}
+		}
+		// Every other entry, including ones owned by others, is carried over
+		for (key, entry) in current.iter() {
+			if !result.removed.contains(key) {
+				let value = (*entry.value).clone();
+				entries.insert(
+					key.clone(),
+					(value, entry.meta.source.clone(), entry.meta.policy),
+				);
+			}
+		}
+		for (key, value, source) in staged {
+			entries.insert(key, (value, source, self.policy));
+		}
+		self.store.replace_all(entries);
+		true
+	}
+
+	/// Loads the selected entries concurrently, at most `concurrency` at a time.
+	///
+	/// Results are ordered by key.
+	async fn load_all<'a>(
+		&self,
+		entries: &'a HashMap<String, String>,
+		selected: impl Fn(&String) -> bool,
+		allow_typed: bool,
+	) -> Vec<(&'a String, Result<Option<Loaded<T>>, LiveError>)> {
+		let pending: Vec<_> = entries
+			.iter()
+			.filter(|(key, _)| selected(key))
+			.map(
+				|(key, load_name)| async move { (key, self.load_raw(key, load_name, allow_typed).await) },
+			)
+			.collect();
+		let mut loaded: Vec<_> = stream::iter(pending)
+			.buffer_unordered(self.concurrency)
+			.collect()
+			.await;
+		loaded.sort_by(|a, b| a.0.cmp(b.0));
+		loaded
+	}
+
+	/// Returns the changes of a reloaded entry, or `None` if nothing changed.
+	///
+	/// An entry is unchanged if its source and content hash match the current
+	/// ones, or if `skip_equal` is enabled and the values compare equal.
+	fn changes(
+		&self,
+		key: &str,
+		value: &T,
+		source: &PathBuf,
+		state: &EntryState,
+	) -> Option<ConfigDiff> {
+		let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+		let previous = entries.get(key);
+		let current = self.overrides.file_entry(&self.store, key);
+		let unchanged = current.is_some_and(|current| {
+			current.meta.source == *source
+				&& ((state.hash.is_some() && state.hash == previous.and_then(|p| p.hash))
+					|| self
+						.equal
+						.as_ref()
+						.is_some_and(|eq| eq(&current.value, value)))
+		});
+		if unchanged {
+			return None;
+		}
+		let old = previous.and_then(|p| p.document.as_ref());
+		Some(match &state.document {
+			Some(new) => ConfigDiff::between(old.unwrap_or(&Value::Null), new),
+			None => ConfigDiff::default(),
+		})
+	}
+
+	/// Resolves a path reported by the loader, which is relative to the directory.
+	async fn source_path(&self, path: PathBuf) -> PathBuf {
+		fs::canonicalize(self.path.join(&path))
+			.await
+			.unwrap_or(path)
+	}
+
+	/// Returns the file `key` is loaded from, if it has one.
+	async fn entry_file(&self, key: &str) -> Result<Option<PathBuf>, LiveError> {
+		let dir = fs::canonicalize(&self.path).await?;
+		// Values served from the last-known-good cache live elsewhere
+		if let Some(entry) = self.overrides.file_entry(&self.store, key)
+			&& entry.meta.source.starts_with(&dir)
+		{
+			return Ok(Some(entry.meta.source));
+		}
+		// The file may exist without having loaded successfully
+		if let ScanMode::Files = self.scan_mode {
+			let mut entries = fs::read_dir(&dir).await?;
+			while let Some(entry) = entries.next_entry().await? {
+				let name = entry.file_name();
+				let name = name.to_string_lossy();
+				if !name.starts_with('.')
+					&& !self.is_defaults_file(&name)
+					&& entry.file_type().await?.is_file()
+					&& self.pattern.extract(&name).as_deref() == Some(key)
+				{
+					return Ok(Some(entry.path()));
+				}
+			}
+		}
+		Ok(None)
+	}
+
+	/// Returns the file a new entry `key` is written to.
+	async fn new_entry_file(&self, key: &str) -> Result<PathBuf, LiveError> {
+		let format = self
+			.write_format
+			.ok_or_else(|| LiveError::Write(format!("{} has no file and no write_format is set", key)))?;
+		let extension = format.extensions()[0];
+		let dir = fs::canonicalize(&self.path).await?;
+		let (name, path) = match &self.scan_mode {
+			ScanMode::Files => {
+				let name = format!("{}.{}", key, extension);
+				let path = dir.join(&name);
+				(name, path)
+			}
+			ScanMode::Subdirs { config_file } => {
+				let path = dir.join(key).join(format!("{}.{}", config_file, extension));
+				(key.to_string(), path)
+			}
+		};
+		// The file has to be found again by the next scan
+		if self.pattern.extract(&name).as_deref() != Some(key)
+			|| self.is_defaults_file(&name)
+			|| self
+				.inheritance
+				.as_ref()
+				.is_some_and(|i| i.template_name(&name).is_some())
+		{
+			return Err(LiveError::Write(format!(
+				"{} does not map back to key {}",
+				name, key
+			)));
+		}
+		Ok(path)
+	}
+
+	/// Adds every entry that inherits from one of `keys`.
+	fn with_dependents(&self, keys: &HashSet<String>) -> HashSet<String> {
+		let mut keys = keys.clone();
+		if let Some(inheritance) = &self.inheritance {
+			let cache = self.raw.lock().unwrap_or_else(|e| e.into_inner());
+			let dependents: Vec<String> = cache
+				.documents
+				.keys()
+				.filter(|key| {
+					inheritance
+						.ancestors(key, &cache.documents)
+						.iter()
+						.any(|parent| keys.contains(parent))
+				})
+				.cloned()
+				.collect();
+			keys.extend(dependents);
+		}
+		keys
+	}
+
+	/// Maps changed paths to the keys they affect.
+	///
+	/// Returns `None` if a path cannot be attributed to specific keys (e.g. the
+	/// defaults file or a file matching no entry), which requires a full rescan.
+	#[cfg(feature = "signal")]
+	fn affected_keys(
+		&self,
+		paths: &[PathBuf],
+		watch_path: &std::path::Path,
+	) -> Option<HashSet<String>> {
+		let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+		let cache = self.raw.lock().unwrap_or_else(|e| e.into_inner());
+		let defaults_includes = self
+			.defaults_includes
+			.lock()
+			.unwrap_or_else(|e| e.into_inner());
+		let mut keys = HashSet::new();
+		for path in paths {
+			// Files included by the defaults change every entry
+			if defaults_includes.contains(path) {
+				return None;
+			}
+			// Included and interpolated files
+			let referencing: Vec<&String> = entries
+				.iter()
+				.map(|(key, state)| (key, &state.files))
+				.chain(cache.template_files.iter())
+				.filter(|(_, files)| files.contains(path))
+				.map(|(key, _)| key)
+				.collect();
+			if !referencing.is_empty() {
+				keys.extend(referencing.into_iter().cloned());
+				continue;
+			}
+
+			let relative = path.strip_prefix(watch_path).ok()?;
+			let mut components = relative.components();
+			let name = components.next()?.as_os_str().to_string_lossy();
+			if name.starts_with('.') {
+				continue;
+			}
+			// Files below a subdirectory only make up an entry in Subdirs mode
+			let nested = components.next().is_some();
+			if self.is_defaults_file(&name) && !nested
+				|| nested && matches!(self.scan_mode, ScanMode::Files)
+			{
+				return None;
+			}
+			let name = self
+				.inheritance
+				.as_ref()
+				.and_then(|i| i.template_name(&name))
+				.unwrap_or(&name);
+			keys.insert(self.pattern.extract(name)?);
+		}
+		Some(keys)
+	}
+
+	/// Loads the directory if it exists by now, or moves the watch closer to it.
+	///
+	/// Returns the canonical path of the directory once it is watched.
+	#[cfg(feature = "signal")]
+	async fn find_dir(&self, watch_set: &mut WatchSet) -> Option<PathBuf> {
+		let found = loop {
+			let found = fs::canonicalize(&self.path).await.ok();
+			match watch_set.sync(self.watch_targets(found.as_deref())) {
+				// A directory on the way appeared, which may hold the rest already
+				Ok(true) if found.is_none() => {}
+				Ok(_) => break found,
+				Err(e) => {
+					if let Some(ref cb) = self.on_error {
+						cb(LiveError::Signal(e));
+					}
+					break found;
+				}
+			}
+		};
+		let found = found?;
+		// Files may have been added before the directory was watched
+		match self.scan().await {
+			Ok((_, errors)) => {
+				if let Some(ref cb) = self.on_error {
+					errors.into_iter().for_each(|e| cb(e));
+				}
+			}
+			Err(e) => {
+				if let Some(ref cb) = self.on_error {
+					cb(e);
+				}
+			}
+		}
+		// Included files are only known after the scan
+		if let Err(e) = watch_set.sync(self.watch_targets(Some(&found)))
+			&& let Some(ref cb) = self.on_error
+		{
+			cb(LiveError::Signal(e));
+		}
+		Some(found)
+	}
+
+	/// Returns the directory itself plus referenced files outside of it, or the
+	/// way to the directory while it does not exist.
+	#[cfg(feature = "signal")]
+	fn watch_targets(&self, watch_path: Option<&std::path::Path>) -> Vec<Target> {
+		let Some(watch_path) = watch_path else {
+			return pending_target(&self.path).into_iter().collect();
+		};
+		let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+		let cache = self.raw.lock().unwrap_or_else(|e| e.into_inner());
+		let defaults_includes = self
+			.defaults_includes
+			.lock()
+			.unwrap_or_else(|e| e.into_inner());
+		let files: HashSet<&PathBuf> = entries
+			.values()
+			.flat_map(|state| &state.files)
+			.chain(cache.template_files.values().flatten())
+			.chain(defaults_includes.iter())
+			.filter(|file| !file.starts_with(watch_path))
+			.collect();
+		std::iter::once(Target::Directory(watch_path.to_path_buf()))
+			.chain(files.into_iter().cloned().map(Target::File))
+			.collect()
+	}
+
+	/// Returns true if `name` is the directory-wide defaults file.
+	fn is_defaults_file(&self, name: &str) -> bool {
+		let Some(defaults) = &self.defaults_file else {
+			return false;
+		};
+		if name == defaults {
+			return true;
+		}
+		// Base names match any extension
+		std::path::Path::new(defaults).extension().is_none()
+			&& name
+				.rsplit_once('.')
+				.is_some_and(|(stem, _)| stem == defaults)
+	}
+
+	/// Loads the directory-wide defaults document, if configured and present.
+	async fn load_defaults(&self) -> Result<Option<Value>, String> {
+		let Some(name) = &self.defaults_file else {
+			return Ok(None);
+		};
+		let load_result = if std::path::Path::new(name).extension().is_some() {
+			self.loader.load_file::<Document>(name).await
+		} else {
+			self.loader.load::<Document>(name).await
+		};
+		match load_result {
+			LoadResult::Ok { value, info } => {
+				let key_path = info.path.to_string_lossy();
+				let mut included = Vec::new();
+				let expanded = expand(&self.loader, &self.path, &key_path, value.0, &mut included).await;
+				// Kept on failure as well, so fixing an included file rescans
+				*self
+					.defaults_includes
+					.lock()
+					.unwrap_or_else(|e| e.into_inner()) = included;
+				expanded.map(Some).map_err(|e| e.to_string())
+			}
+			LoadResult::NotFound => {
+				self
+					.defaults_includes
+					.lock()
+					.unwrap_or_else(|e| e.into_inner())
+					.clear();
+				Ok(None)
+			}
+			LoadResult::Invalid(e) => Err(format!("[{}] {}", name, e)),
+		}
+	}
+
+	/// Loads a single entry or template without merging anything into it.
+	///
+	/// With `allow_typed`, entries that cannot be loaded as an untyped document are
+	/// deserialized directly. Returns `Ok(None)` if the entry's config file does not exist.
+	async fn load_raw(
+		&self,
+		key: &str,
+		load_name: &str,
+		allow_typed: bool,
+	) -> Result<Option<Loaded<T>>, LiveError> {
+		let loader = &self.loader;
+		// Files mode: load_file (exact path)
+		// Subdirs mode: load (probe extensions)
+		let load_result = match self.scan_mode {
+			ScanMode::Files => loader.load_file::<Document>(load_name).await,
+			ScanMode::Subdirs { .. } => loader.load::<Document>(load_name).await,
+		};
+
+		match load_result {
+			LoadResult::Ok { value, info } => {
+				// Included files live in the watched directory
+				let key_path = info.path.to_string_lossy();
+				let mut included = Vec::new();
+				let document = expand(loader, &self.path, &key_path, value.0, &mut included).await?;
+				let source_path = self.source_path(info.path).await;
+				Ok(Some(Loaded::Document(document, source_path, included)))
+			}
+			LoadResult::NotFound => Ok(None),
+			LoadResult::Invalid(e) if !allow_typed => Err(LiveError::Load(e)),
+			// Non self-describing formats (e.g. postcard) cannot be loaded as an
+			// untyped document, so they are deserialized directly.
+			LoadResult::Invalid(e) => {
+				let typed = match self.scan_mode {
+					ScanMode::Files => loader.load_file::<T>(load_name).await,
+					ScanMode::Subdirs { .. } => loader.load::<T>(load_name).await,
+				};
+				match typed {
+					LoadResult::Ok { mut value, info } => {
+						value.set_context(key);
+						value.validate_config()?;
+						let source_path = self.source_path(info.path).await;
+						Ok(Some(Loaded::Typed(value, source_path)))
+					}
+					_ => Err(LiveError::Load(e)),
+				}
+			}
+		}
+	}
+
+	/// Resolves inheritance for an entry, merges it over `defaults`, applies
+	/// environment overrides and interpolation, then decodes it.
+	///
+	/// Also returns the merged document and the files it references.
+	async fn finish_entry(
+		&self,
+		key: &str,
+		load_name: &str,
+		entry: Value,
+		defaults: Option<&Value>,
+		documents: &HashMap<String, Result<Value, String>>,
+	) -> Result<(T, EntryState), LiveError> {
+		let entry = match &self.inheritance {
+			Some(inheritance) => inheritance
+				.resolve(key, documents)
+				.map_err(FmtError::ParseError)?,
+			None => entry,
+		};
+
+		let mut document = defaults.cloned().unwrap_or(Value::Null);
+		merge(&mut document, entry);
+		if let Some(env) = &self.env {
+			env.apply(&mut document, Some(key));
+		}
+		let files = if self.interpolate {
+			let entry_path = self.path.join(load_name);
+			let base_dir = entry_path.parent().unwrap_or(&self.path);
+			interpolate(&mut document, &self.path, base_dir, key).await?
+		} else {
+			Vec::new()
+		};
+		let state = EntryState {
+			hash: Some(content_hash(&document)),
+			document: Some(document.clone()),
+			files,
+		};
+		Ok((decode::<T>(document, key)?, state))
+	}
+}
+
+/// What an entry's last successful load was built from.
+#[derive(Clone, Default)]
+struct EntryState {
+	/// The merged document, diffed against on the next load.
+	document: Option<Value>,
+	/// Content hash of the merged document.
+	hash: Option<u64>,
+	/// Files referenced through `$include` and `${file:...}`.
+	#[cfg_attr(not(feature = "signal"), allow(dead_code))]
+	files: Vec<PathBuf>,
+}
+
+/// Raw documents of entries and templates, kept when inheritance is enabled.
+#[derive(Default)]
+struct RawCache {
+	documents: HashMap<String, Result<Value, String>>,
+	/// Files included by each template.
+	#[cfg_attr(not(feature = "signal"), allow(dead_code))]
+	template_files: HashMap<String, Vec<PathBuf>>,
+}
+
+/// A raw directory entry, before inheritance, defaults and overrides are applied.
+enum Loaded<T> {
+	/// The document, its source and the files it includes.
+	Document(Value, PathBuf, Vec<PathBuf>),
+	Typed(T, PathBuf),
 }
 
 impl<T> std::fmt::Debug for LiveDir<T>
@@ -512,14 +2000,21 @@ where
 	T: std::fmt::Debug,
 {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let inner = &self.inner;
 		let mut s = f.debug_struct("LiveDir");
-		s.field("store", &self.store);
-		s.field("loader", &self.loader);
-		s.field("path", &self.path);
-		s.field("pattern", &self.pattern);
-		s.field("scan_mode", &self.scan_mode);
-		s.field("policy", &self.policy);
-		s.field("max_entries", &self.max_entries);
+		s.field("store", &inner.store);
+		s.field("loader", &inner.loader);
+		s.field("path", &inner.path);
+		s.field("pattern", &inner.pattern);
+		s.field("scan_mode", &inner.scan_mode);
+		s.field("policy", &inner.policy);
+		s.field("commit_mode", &inner.commit_mode);
+		s.field("max_entries", &inner.max_entries);
+		s.field("concurrency", &inner.concurrency);
+		s.field("env", &inner.env);
+		s.field("defaults_file", &inner.defaults_file);
+		s.field("inheritance", &inner.inheritance);
+		s.field("interpolate", &inner.interpolate);
 		#[cfg(feature = "signal")]
 		s.field("watching", &self.watch_state.is_some());
 		s.finish_non_exhaustive()
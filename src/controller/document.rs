@@ -0,0 +1,149 @@
+/* src/controller/document.rs */
+
+//!
+//! Untyped documents used to merge configuration sources before deserialization.
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use fmtstruct::{FmtError, PreProcess, ValidateConfig};
+use serde::de::{Deserialize, DeserializeOwned, Deserializer};
+use serde_json::Value;
+
+/// Format-agnostic intermediate representation of a loaded file.
+///
+/// Loading through `DynLoader::load::<Document>` keeps the sandboxed source and
+/// format detection of the loader while deferring deserialization into `T` until
+/// all sources have been merged.
+pub(crate) struct Document(pub Value);
+
+impl<'de> Deserialize<'de> for Document {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		Value::deserialize(deserializer).map(Document)
+	}
+}
+
+impl PreProcess for Document {}
+
+// Validation is deferred until the merged document is decoded into `T`.
+#[cfg(feature = "validate")]
+impl validator::Validate for Document {
+	fn validate(&self) -> Result<(), validator::ValidationErrors> {
+		Ok(())
+	}
+}
+
+/// Returns a hash of the document's content, used to detect no-op reloads.
+pub(crate) fn content_hash(document: &Value) -> u64 {
+	let mut hasher = DefaultHasher::new();
+	document.to_string().hash(&mut hasher);
+	hasher.finish()
+}
+
+/// Deep-merges `overlay` into `base`.
+///
+/// Objects are merged key by key; any other value in `overlay` replaces the
+/// corresponding value in `base`.
+pub(crate) fn merge(base: &mut Value, overlay: Value) {
+	match (base, overlay) {
+		(Value::Object(base), Value::Object(overlay)) => {
+			for (key, value) in overlay {
+				match base.get_mut(&key) {
+					Some(slot) => merge(slot, value),
+					None => {
+						base.insert(key, value);
+					}
+				}
+			}
+		}
+		(slot, value) => *slot = value,
+	}
+}
+
+/// Applies the changes from `before` to `after` to `base`, one of the documents
+/// `before` was merged from.
+///
+/// Only changed values are written, so whatever `base` does not set itself
+/// stays with the source that set it. Objects are compared key by key; any
+/// other changed value replaces the one in `base`, and removed keys are
+/// removed from it.
+pub(crate) fn patch(base: &mut Value, before: &Value, after: &Value) {
+	match (before, after) {
+		(before, after) if before == after => {}
+		(Value::Object(before), Value::Object(after)) => {
+			if !base.is_object() {
+				*base = Value::Object(Default::default());
+			}
+			let Value::Object(base) = base else {
+				return;
+			};
+			for (key, value) in after {
+				match before.get(key) {
+					Some(old) if old == value => {}
+					Some(old) => patch(base.entry(key).or_insert(Value::Null), old, value),
+					None => {
+						base.insert(key.clone(), value.clone());
+					}
+				}
+			}
+			for key in before.keys().filter(|key| !after.contains_key(*key)) {
+				base.remove(key);
+			}
+		}
+		(_, after) => *base = after.clone(),
+	}
+}
+
+/// Deserializes a merged document into `T` and runs the usual lifecycle hooks.
+pub(crate) fn decode<T>(document: Value, context: &str) -> Result<T, FmtError>
+where
+	T: DeserializeOwned + PreProcess + ValidateConfig,
+{
+	let mut value: T =
+		serde_json::from_value(document).map_err(|e| FmtError::ParseError(e.to_string()))?;
+	value.pre_process();
+	value.set_context(context);
+	value.validate_config()?;
+	Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn test_merge_nested_objects() {
+		let mut base = json!({"db": {"host": "localhost", "port": 5432}, "debug": false});
+		merge(&mut base, json!({"db": {"port": 5433}, "debug": true}));
+		assert_eq!(
+			base,
+			json!({"db": {"host": "localhost", "port": 5433}, "debug": true})
+		);
+	}
+
+	#[test]
+	fn test_patch_writes_changes_only() {
+		let before = json!({"db": {"host": "localhost", "port": 9999}, "name": "a", "tags": ["x"]});
+		let after = json!({"db": {"host": "db.internal", "port": 9999}, "name": "a", "new": 1});
+		let mut base = json!({"name": "${NAME}", "tags": ["x"]});
+		patch(&mut base, &before, &after);
+		assert_eq!(
+			base,
+			json!({"db": {"host": "db.internal"}, "name": "${NAME}", "new": 1})
+		);
+	}
+
+	#[test]
+	fn test_merge_replaces_non_objects() {
+		let mut base = json!({"hosts": ["a", "b"], "name": {"first": "x"}});
+		merge(&mut base, json!({"hosts": ["c"], "name": "flat"}));
+		assert_eq!(base, json!({"hosts": ["c"], "name": "flat"}));
+
+		let mut base = Value::Null;
+		merge(&mut base, json!({"val": 1}));
+		assert_eq!(base, json!({"val": 1}));
+	}
+}
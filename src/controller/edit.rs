@@ -0,0 +1,400 @@
+/* src/controller/edit.rs */
+
+//!
+//! In-place edits of single fields that keep comments and layout intact.
+//!
+//! Only the text of the edited value is replaced. JSON and TOML documents may be
+//! edited at any path; YAML documents only at scalar values inside block
+//! mappings.
+
+use std::path::Path;
+
+use serde_json::Value;
+
+use super::LiveError;
+
+/// One step of a field path such as `listeners[0].bind`.
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+	Key(String),
+	Index(usize),
+}
+
+/// Replaces the value at `field` in `text`, the content of `path`, with `value`.
+pub(crate) fn set_field(
+	text: &str,
+	path: &Path,
+	field: &str,
+	value: &Value,
+) -> Result<String, LiveError> {
+	let failed = |reason: &str| LiveError::Write(format!("{}: {}", field, reason));
+	let segments = parse_path(field).ok_or_else(|| failed("invalid field path"))?;
+	let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+	let (span, rendered) = match extension {
+		"json" => {
+			let span = json::locate(text, &segments).map_err(|e| failed(&e))?;
+			let rendered = serde_json::to_string(value).map_err(|e| failed(&e.to_string()))?;
+			(span, rendered)
+		}
+		#[cfg(feature = "toml")]
+		"toml" => {
+			let span = toml_span(text, &segments).map_err(|e| failed(&e))?;
+			let rendered = toml::Value::try_from(value)
+				.map(|v| v.to_string())
+				.map_err(|e| failed(&e.to_string()))?;
+			(span, rendered)
+		}
+		#[cfg(feature = "yaml")]
+		"yaml" | "yml" => {
+			let span = yaml::locate(text, &segments).map_err(|e| failed(&e))?;
+			(span, yaml::render(value).map_err(|e| failed(&e))?)
+		}
+		_ => return Err(failed("format cannot be edited")),
+	};
+	let mut edited = String::with_capacity(text.len() + rendered.len());
+	edited.push_str(&text[..span.0]);
+	edited.push_str(&rendered);
+	edited.push_str(&text[span.1..]);
+	Ok(edited)
+}
+
+/// Parses `db.port` or `listeners[0].bind` into segments.
+fn parse_path(field: &str) -> Option<Vec<Segment>> {
+	let mut segments = Vec::new();
+	for part in field.split('.') {
+		let (key, mut rest) = part.split_at(part.find('[').unwrap_or(part.len()));
+		if key.is_empty() {
+			return None;
+		}
+		segments.push(Segment::Key(key.to_string()));
+		while let Some(inner) = rest.strip_prefix('[') {
+			let (index, tail) = inner.split_once(']')?;
+			segments.push(Segment::Index(index.parse().ok()?));
+			rest = tail;
+		}
+		if !rest.is_empty() {
+			return None;
+		}
+	}
+	Some(segments)
+}
+
+/// Locates the value at `segments` in a TOML document.
+#[cfg(feature = "toml")]
+fn toml_span(text: &str, segments: &[Segment]) -> Result<(usize, usize), String> {
+	use toml::de::{DeTable, DeValue};
+
+	let document = DeTable::parse(text).map_err(|e| e.to_string())?;
+	let mut table = document.get_ref();
+	let mut current: Option<&toml::Spanned<DeValue<'_>>> = None;
+	for segment in segments {
+		let found = match (current.map(|v| v.get_ref()), segment) {
+			(None, Segment::Key(key)) => table
+				.iter()
+				.find(|(k, _)| k.get_ref().as_ref() == key.as_str()),
+			(Some(DeValue::Table(t)), Segment::Key(key)) => {
+				table = t;
+				table
+					.iter()
+					.find(|(k, _)| k.get_ref().as_ref() == key.as_str())
+			}
+			(Some(DeValue::Array(array)), Segment::Index(index)) => {
+				current = Some(array.get(*index).ok_or("index out of bounds")?);
+				continue;
+			}
+			_ => return Err("no such field".to_string()),
+		};
+		current = Some(found.ok_or("no such field")?.1);
+	}
+	let value = current.ok_or("no such field")?;
+	if let DeValue::Table(_) = value.get_ref() {
+		// Tables may be spread over several headers
+		return Err("tables cannot be replaced in place".to_string());
+	}
+	let span = value.span();
+	Ok((span.start, span.end))
+}
+
+/// A minimal JSON scanner that finds the byte range of a value.
+mod json {
+	use super::Segment;
+
+	pub fn locate(text: &str, segments: &[Segment]) -> Result<(usize, usize), String> {
+		let mut scanner = Scanner {
+			bytes: text.as_bytes(),
+			pos: 0,
+		};
+		scanner.skip_ws();
+		for segment in segments {
+			match segment {
+				Segment::Key(key) => scanner.enter_key(key)?,
+				Segment::Index(index) => scanner.enter_index(*index)?,
+			}
+		}
+		let start = scanner.pos;
+		scanner.skip_value()?;
+		Ok((start, scanner.pos))
+	}
+
+	struct Scanner<'a> {
+		bytes: &'a [u8],
+		pos: usize,
+	}
+
+	impl Scanner<'_> {
+		fn peek(&self) -> Option<u8> {
+			self.bytes.get(self.pos).copied()
+		}
+
+		fn skip_ws(&mut self) {
+			while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+				self.pos += 1;
+			}
+		}
+
+		fn expect(&mut self, byte: u8) -> Result<(), String> {
+			self.skip_ws();
+			if self.peek() != Some(byte) {
+				return Err(format!("expected '{}' at byte {}", byte as char, self.pos));
+			}
+			self.pos += 1;
+			self.skip_ws();
+			Ok(())
+		}
+
+		/// Moves to the value of `key` in the object at the current position.
+		fn enter_key(&mut self, key: &str) -> Result<(), String> {
+			self.expect(b'{')?;
+			while self.peek() != Some(b'}') {
+				let start = self.pos;
+				self.skip_string()?;
+				let name: String =
+					serde_json::from_slice(&self.bytes[start..self.pos]).map_err(|e| e.to_string())?;
+				self.expect(b':')?;
+				if name == key {
+					return Ok(());
+				}
+				self.skip_value()?;
+				self.skip_ws();
+				if self.peek() == Some(b',') {
+					self.expect(b',')?;
+				}
+			}
+			Err("no such field".to_string())
+		}
+
+		/// Moves to element `index` of the array at the current position.
+		fn enter_index(&mut self, index: usize) -> Result<(), String> {
+			self.expect(b'[')?;
+			for _ in 0..index {
+				if self.peek() == Some(b']') {
+					return Err("index out of bounds".to_string());
+				}
+				self.skip_value()?;
+				self.expect(b',')?;
+			}
+			if self.peek() == Some(b']') {
+				return Err("index out of bounds".to_string());
+			}
+			Ok(())
+		}
+
+		fn skip_string(&mut self) -> Result<(), String> {
+			if self.peek() != Some(b'"') {
+				return Err(format!("expected a string at byte {}", self.pos));
+			}
+			self.pos += 1;
+			while let Some(byte) = self.peek() {
+				self.pos += 1;
+				match byte {
+					b'\\' => self.pos += 1,
+					b'"' => return Ok(()),
+					_ => {}
+				}
+			}
+			Err("unterminated string".to_string())
+		}
+
+		fn skip_value(&mut self) -> Result<(), String> {
+			match self.peek() {
+				Some(b'"') => self.skip_string(),
+				Some(open @ (b'{' | b'[')) => {
+					let close = if open == b'{' { b'}' } else { b']' };
+					self.expect(open)?;
+					while self.peek() != Some(close) {
+						if open == b'{' {
+							self.skip_string()?;
+							self.expect(b':')?;
+						}
+						self.skip_value()?;
+						self.skip_ws();
+						if self.peek() == Some(b',') {
+							self.expect(b',')?;
+						} else if self.peek() != Some(close) {
+							return Err(format!("unexpected byte at {}", self.pos));
+						}
+					}
+					self.pos += 1;
+					Ok(())
+				}
+				Some(_) => {
+					// Numbers, booleans and null
+					while self
+						.peek()
+						.is_some_and(|b| !matches!(b, b',' | b'}' | b']') && !b.is_ascii_whitespace())
+					{
+						self.pos += 1;
+					}
+					Ok(())
+				}
+				None => Err("unexpected end of document".to_string()),
+			}
+		}
+	}
+}
+
+/// Line-based editing of scalars in YAML block mappings.
+#[cfg(feature = "yaml")]
+mod yaml {
+	use serde_json::Value;
+
+	use super::Segment;
+
+	pub fn locate(text: &str, segments: &[Segment]) -> Result<(usize, usize), String> {
+		let mut lines = Vec::new();
+		let mut offset = 0;
+		for line in text.split_inclusive('\n') {
+			lines.push((offset, line.trim_end_matches(['\n', '\r'])));
+			offset += line.len();
+		}
+
+		let mut from = 0;
+		let mut parent: Option<usize> = None;
+		for (depth, segment) in segments.iter().enumerate() {
+			let Segment::Key(key) = segment else {
+				return Err("sequences cannot be edited in place".to_string());
+			};
+			let mut child_indent = None;
+			let mut found = None;
+			for (i, (start, line)) in lines.iter().enumerate().skip(from) {
+				let content = line.trim_start();
+				if content.is_empty() || content.starts_with('#') || content.starts_with("---") {
+					continue;
+				}
+				let indent = line.len() - content.len();
+				if parent.is_some_and(|p| indent <= p) {
+					break;
+				}
+				let expected = *child_indent.get_or_insert(indent);
+				if indent != expected {
+					continue;
+				}
+				if let Some(rest) = strip_key(content, key) {
+					found = Some((i, start + indent + (content.len() - rest.len()), rest));
+					break;
+				}
+			}
+			let (i, value_start, rest) = found.ok_or("no such field")?;
+			let value = rest.trim_start();
+			let value_start = value_start + (rest.len() - value.len());
+			let scalar = scalar_len(value)?;
+			if depth + 1 == segments.len() {
+				if scalar == 0 {
+					return Err("only scalars can be edited in place".to_string());
+				}
+				return Ok((value_start, value_start + scalar));
+			}
+			if scalar != 0 {
+				return Err("no such field".to_string());
+			}
+			parent = child_indent;
+			from = i + 1;
+		}
+		Err("no such field".to_string())
+	}
+
+	/// Returns what follows `key:` if `content` starts with that mapping key.
+	fn strip_key<'a>(content: &'a str, key: &str) -> Option<&'a str> {
+		let rest = [
+			format!("\"{}\"", key),
+			format!("'{}'", key),
+			key.to_string(),
+		]
+		.iter()
+		.find_map(|candidate| content.strip_prefix(candidate.as_str()))?;
+		let rest = rest.trim_start_matches(' ').strip_prefix(':')?;
+		(rest.is_empty() || rest.starts_with([' ', '\t'])).then_some(rest)
+	}
+
+	/// Returns the length of the scalar starting `value`, excluding any comment,
+	/// or 0 if there is none.
+	fn scalar_len(value: &str) -> Result<usize, String> {
+		if value.is_empty() || value.starts_with('#') {
+			return Ok(0);
+		}
+		if value.starts_with(['|', '>', '&', '*', '!', '[', '{']) {
+			return Err("only plain and quoted scalars can be edited in place".to_string());
+		}
+		let quote = value.as_bytes()[0];
+		if quote == b'"' || quote == b'\'' {
+			let mut escaped = false;
+			for (i, byte) in value.bytes().enumerate().skip(1) {
+				match byte {
+					b'\\' if quote == b'"' && !escaped => escaped = true,
+					b if b == quote && !escaped => return Ok(i + 1),
+					_ => escaped = false,
+				}
+			}
+			return Err("multi-line scalars cannot be edited in place".to_string());
+		}
+		let end = value.find(" #").unwrap_or(value.len());
+		Ok(value[..end].trim_end().len())
+	}
+
+	/// Renders `value` on a single line: scalars as YAML, anything else in flow
+	/// style, which YAML shares with JSON.
+	pub fn render(value: &Value) -> Result<String, String> {
+		if !matches!(value, Value::Array(_) | Value::Object(_)) {
+			let rendered = serde_yaml::to_string(value).map_err(|e| e.to_string())?;
+			let rendered = rendered.trim_end();
+			if !rendered.contains('\n') {
+				return Ok(rendered.to_string());
+			}
+		}
+		serde_json::to_string(value).map_err(|e| e.to_string())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn test_parse_path() {
+		assert_eq!(
+			parse_path("listeners[0].bind"),
+			Some(vec![
+				Segment::Key("listeners".to_string()),
+				Segment::Index(0),
+				Segment::Key("bind".to_string()),
+			])
+		);
+		assert_eq!(parse_path("a..b"), None);
+		assert_eq!(parse_path("a[x]"), None);
+	}
+
+	#[test]
+	fn test_set_field_json() {
+		let text = "{\n  \"db\": {\"host\": \"a\", \"port\": 5432},\n  \"tags\": [1, 2]\n}\n";
+		let path = Path::new("app.json");
+		let edited = set_field(text, path, "db.port", &json!(5433)).unwrap();
+		assert_eq!(
+			edited,
+			"{\n  \"db\": {\"host\": \"a\", \"port\": 5433},\n  \"tags\": [1, 2]\n}\n"
+		);
+		let edited = set_field(text, path, "tags[1]", &json!("x")).unwrap();
+		assert!(edited.contains("[1, \"x\"]"));
+		assert!(set_field(text, path, "db.user", &json!(1)).is_err());
+	}
+}
@@ -0,0 +1,211 @@
+/* src/controller/env.rs */
+
+//!
+//! Environment variable overrides mapped onto nested configuration fields.
+
+use serde_json::{Map, Value};
+
+/// How environment variable names are mapped onto field names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EnvCase {
+	/// Lowercase each segment and match existing fields case-insensitively.
+	///
+	/// `APP__DB__MAX_CONNS` → `db.max_conns`
+	#[default]
+	Lower,
+	/// Use segments exactly as written in the variable name.
+	Preserve,
+}
+
+/// Maps environment variables onto nested configuration fields.
+///
+/// With prefix `APP` and the default separator `__`, `APP__DB__PORT=5433`
+/// sets `db.port` to `5433`. Values are parsed as JSON when possible (numbers,
+/// booleans, arrays, quoted strings) and used as plain strings otherwise.
+///
+/// For `LiveDir`, the first segment after the prefix selects the entry key:
+/// `APP__LISTENERS__443__BIND` with prefix `APP__LISTENERS` sets `bind` on key `443`.
+///
+/// The environment is read on every load, so a manual `reload()` picks up changes.
+#[derive(Debug, Clone)]
+pub struct EnvOverrides {
+	prefix: String,
+	separator: String,
+	case: EnvCase,
+}
+
+impl EnvOverrides {
+	/// Creates overrides for variables starting with `prefix` followed by the separator.
+	pub fn new(prefix: impl Into<String>) -> Self {
+		Self {
+			prefix: prefix.into(),
+			separator: "__".to_string(),
+			case: EnvCase::default(),
+		}
+	}
+
+	/// Sets the separator between prefix and nested segments (default `__`).
+	pub fn separator(mut self, separator: impl Into<String>) -> Self {
+		self.separator = separator.into();
+		self
+	}
+
+	/// Sets how variable segments map onto field names.
+	pub fn case(mut self, case: EnvCase) -> Self {
+		self.case = case;
+		self
+	}
+
+	/// Applies all matching variables of the current environment to `document`.
+	///
+	/// With `scope`, only variables whose first segment matches the scope are applied
+	/// (with that segment removed). Matching variables whose value is not valid
+	/// UTF-8 are skipped, as are names that are not.
+	pub(crate) fn apply(&self, document: &mut Value, scope: Option<&str>) {
+		// `env::vars()` panics on the first non-UTF-8 variable, matching or not
+		let mut vars: Vec<(String, String)> = std::env::vars_os()
+			.filter_map(|(name, value)| {
+				let name = name.into_string().ok()?;
+				self.segments(&name)?;
+				Some((name, value.into_string().ok()?))
+			})
+			.collect();
+		// Apply shorter paths first so `APP__DB__PORT` wins over `APP__DB`
+		vars.sort();
+		self.apply_vars(document, scope, vars);
+	}
+
+	fn apply_vars<I>(&self, document: &mut Value, scope: Option<&str>, vars: I)
+	where
+		I: IntoIterator<Item = (String, String)>,
+	{
+		for (name, raw) in vars {
+			let Some(mut path) = self.segments(&name) else {
+				continue;
+			};
+			if let Some(scope) = scope {
+				if path.len() < 2 || !self.matches(&path[0], scope) {
+					continue;
+				}
+				path.remove(0);
+			}
+			let value = serde_json::from_str(&raw).unwrap_or(Value::String(raw));
+			self.set(document, &path, value);
+		}
+	}
+
+	fn segments(&self, name: &str) -> Option<Vec<String>> {
+		if self.separator.is_empty() {
+			return None;
+		}
+		let rest = name
+			.strip_prefix(self.prefix.as_str())?
+			.strip_prefix(self.separator.as_str())?;
+		let segments: Vec<String> = rest
+			.split(self.separator.as_str())
+			.map(|s| match self.case {
+				EnvCase::Lower => s.to_lowercase(),
+				EnvCase::Preserve => s.to_string(),
+			})
+			.collect();
+		if segments.iter().any(String::is_empty) {
+			return None;
+		}
+		Some(segments)
+	}
+
+	fn matches(&self, segment: &str, field: &str) -> bool {
+		match self.case {
+			EnvCase::Lower => segment.eq_ignore_ascii_case(field),
+			EnvCase::Preserve => segment == field,
+		}
+	}
+
+	fn set(&self, target: &mut Value, path: &[String], value: Value) {
+		let Some((head, rest)) = path.split_first() else {
+			*target = value;
+			return;
+		};
+
+		if let Value::Array(items) = target
+			&& let Ok(index) = head.parse::<usize>()
+			&& index < items.len()
+		{
+			self.set(&mut items[index], rest, value);
+			return;
+		}
+
+		if !target.is_object() {
+			*target = Value::Object(Map::new());
+		}
+		let Value::Object(map) = target else {
+			return;
+		};
+		let field = map
+			.keys()
+			.find(|k| self.matches(head, k))
+			.cloned()
+			.unwrap_or_else(|| head.clone());
+		self.set(map.entry(field).or_insert(Value::Null), rest, value);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	fn vars(list: &[(&str, &str)]) -> Vec<(String, String)> {
+		list
+			.iter()
+			.map(|(k, v)| (k.to_string(), v.to_string()))
+			.collect()
+	}
+
+	#[test]
+	fn test_env_nested_mapping() {
+		let env = EnvOverrides::new("APP");
+		let mut doc = json!({"db": {"port": 5432, "maxConns": 4}, "name": "app"});
+		env.apply_vars(
+			&mut doc,
+			None,
+			vars(&[
+				("APP__DB__PORT", "5433"),
+				("APP__DB__MAXCONNS", "8"),
+				("APP__NAME", "\"123\""),
+				("APP__DEBUG", "true"),
+				("OTHER__NAME", "ignored"),
+			]),
+		);
+		assert_eq!(
+			doc,
+			json!({"db": {"port": 5433, "maxConns": 8}, "name": "123", "debug": true})
+		);
+	}
+
+	#[test]
+	fn test_env_scoped_mapping() {
+		let env = EnvOverrides::new("APP__LISTENERS");
+		let mut doc = json!({"bind": "0.0.0.0:443", "hosts": ["a", "b"]});
+		env.apply_vars(
+			&mut doc,
+			Some("443"),
+			vars(&[
+				("APP__LISTENERS__443__BIND", "127.0.0.1:443"),
+				("APP__LISTENERS__443__HOSTS__1", "c"),
+				("APP__LISTENERS__80__BIND", "ignored"),
+			]),
+		);
+		assert_eq!(doc, json!({"bind": "127.0.0.1:443", "hosts": ["a", "c"]}));
+	}
+
+	#[test]
+	fn test_env_custom_separator_and_case() {
+		let env = EnvOverrides::new("APP")
+			.separator("_")
+			.case(EnvCase::Preserve);
+		let mut doc = json!({});
+		env.apply_vars(&mut doc, None, vars(&[("APP_Db_Port", "1")]));
+		assert_eq!(doc, json!({"Db": {"Port": 1}}));
+	}
+}
@@ -20,7 +20,44 @@ pub enum LiveError {
 	#[error("Signal error: {0}")]
 	Signal(#[from] fsig::Error),
 
-	#[error("Config not loaded yet. Call load() before watch().")]
+	#[cfg(feature = "signal")]
+	#[error("Watch events dropped: {0}, reloading everything")]
+	Lagged(u64),
+
+	#[cfg(feature = "signal")]
+	#[error("Watcher stopped: {0}")]
+	WatcherStopped(String),
+
+	#[cfg(feature = "signal")]
+	#[error("Watcher restored after {0} attempts, reloading everything")]
+	WatcherRestored(u32),
+
+	#[cfg(feature = "signal")]
+	#[error("Watcher unavailable, polling instead: {0}")]
+	PollingFallback(String),
+
+	#[error("Include error: {0}")]
+	Include(String),
+
+	#[error("Unresolved reference: {0}")]
+	Unresolved(String),
+
+	#[error("Interpolation error: {0}")]
+	Interpolate(String),
+
+	#[error("Rejected: {0}")]
+	Rejected(String),
+
+	#[error("Rolled back: {0}")]
+	RolledBack(String),
+
+	#[error("Write error: {0}")]
+	Write(String),
+
+	#[error("Unknown generation: {0}")]
+	UnknownGeneration(u64),
+
+	#[error("Config not loaded yet. Call load() first.")]
 	NotLoaded,
 
 	#[error("Entry limit exceeded: {0}")]
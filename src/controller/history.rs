@@ -0,0 +1,183 @@
+/* src/controller/history.rs */
+
+//!
+//! Generation numbers and a bounded history of previous values.
+
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use atomhold::Store;
+
+use super::LiveError;
+
+/// Number of revisions kept per key unless configured otherwise.
+pub(crate) const DEFAULT_HISTORY_LIMIT: usize = 10;
+
+/// A value a key held, as recorded by a successful reload or rollback.
+#[derive(Debug)]
+pub struct Revision<T> {
+	/// Generation the value was stored at.
+	pub generation: u64,
+	pub value: Arc<T>,
+	/// File the value was loaded from.
+	pub source: PathBuf,
+	pub loaded_at: SystemTime,
+	/// Content hash of the merged document, if the value was decoded from one.
+	pub hash: Option<u64>,
+}
+
+impl<T> Clone for Revision<T> {
+	fn clone(&self) -> Self {
+		Self {
+			generation: self.generation,
+			value: Arc::clone(&self.value),
+			source: self.source.clone(),
+			loaded_at: self.loaded_at,
+			hash: self.hash,
+		}
+	}
+}
+
+/// Per-key revisions of a controller, sharing one generation counter.
+pub(crate) struct History<T> {
+	limit: usize,
+	generation: u64,
+	/// Oldest revision first.
+	revisions: HashMap<String, VecDeque<Revision<T>>>,
+}
+
+impl<T> History<T> {
+	pub fn new(limit: usize) -> Self {
+		Self {
+			limit,
+			generation: 0,
+			revisions: HashMap::new(),
+		}
+	}
+
+	/// Returns the latest generation, or 0 before the first change.
+	pub fn current(&self) -> u64 {
+		self.generation
+	}
+
+	/// Starts a new generation.
+	pub fn advance(&mut self) -> u64 {
+		self.generation += 1;
+		self.generation
+	}
+
+	/// Records the value `key` holds at `generation`, dropping the oldest
+	/// revisions beyond the limit.
+	pub fn record(
+		&mut self,
+		key: &str,
+		generation: u64,
+		value: Arc<T>,
+		source: PathBuf,
+		hash: Option<u64>,
+	) {
+		if self.limit == 0 {
+			return;
+		}
+		let revisions = self.revisions.entry(key.to_string()).or_default();
+		revisions.push_back(Revision {
+			generation,
+			value,
+			source,
+			loaded_at: SystemTime::now(),
+			hash,
+		});
+		while revisions.len() > self.limit {
+			revisions.pop_front();
+		}
+	}
+
+	/// Returns the recorded revisions of `key`, oldest first.
+	pub fn list(&self, key: &str) -> Vec<Revision<T>> {
+		self
+			.revisions
+			.get(key)
+			.map(|revisions| revisions.iter().cloned().collect())
+			.unwrap_or_default()
+	}
+
+	/// Restores every key of `keys` to the value it held at `generation`.
+	///
+	/// Keys that are no longer in the store, have no revision that old or are
+	/// `skip`ped are left untouched. The restored values form a new generation.
+	/// Returns the restored keys, or `LiveError::UnknownGeneration` if no key
+	/// has a revision that old.
+	pub fn rollback<'a>(
+		&mut self,
+		store: &Store<T>,
+		keys: impl IntoIterator<Item = &'a String>,
+		generation: u64,
+		skip: impl Fn(&str) -> bool,
+	) -> Result<Vec<String>, LiveError>
+	where
+		T: Clone + Send + Sync,
+	{
+		if generation == 0 || generation > self.generation {
+			return Err(LiveError::UnknownGeneration(generation));
+		}
+		let mut known = false;
+		let mut targets = Vec::new();
+		for key in keys {
+			let Some(revision) = self
+				.revisions
+				.get(key)
+				.and_then(|revisions| revisions.iter().rev().find(|r| r.generation <= generation))
+			else {
+				continue;
+			};
+			known = true;
+			if skip(key) {
+				continue;
+			}
+			if let Some(current) = store.get_entry(key)
+				&& !Arc::ptr_eq(&current.value, &revision.value)
+			{
+				targets.push((key.clone(), revision.clone(), current.meta.policy));
+			}
+		}
+		if !known {
+			return Err(LiveError::UnknownGeneration(generation));
+		}
+		if targets.is_empty() {
+			return Ok(Vec::new());
+		}
+
+		let rolled_back = self.advance();
+		let mut restored = Vec::with_capacity(targets.len());
+		targets.sort_by(|a, b| a.0.cmp(&b.0));
+		for (key, revision, policy) in targets {
+			let value = (*revision.value).clone();
+			store.insert(key.clone(), value, revision.source.clone(), policy);
+			if let Some(current) = store.get(&key) {
+				self.record(&key, rolled_back, current, revision.source, revision.hash);
+			}
+			restored.push(key);
+		}
+		Ok(restored)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_history_is_bounded() {
+		let mut history = History::new(2);
+		for i in 1..=3 {
+			let generation = history.advance();
+			history.record("a", generation, Arc::new(i), PathBuf::from("a.json"), None);
+		}
+		let generations: Vec<u64> = history.list("a").iter().map(|r| r.generation).collect();
+		assert_eq!(generations, vec![2, 3]);
+		assert_eq!(history.current(), 3);
+		assert!(history.list("b").is_empty());
+	}
+}
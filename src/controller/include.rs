@@ -0,0 +1,213 @@
+/* src/controller/include.rs */
+
+//!
+//! `$include` directives splitting one configuration across multiple files.
+
+use std::future::Future;
+use std::path::{Component, Path, PathBuf};
+use std::pin::Pin;
+
+use fmtstruct::{DynLoader, LoadResult};
+use serde_json::Value;
+
+use super::LiveError;
+use super::document::{Document, merge};
+
+/// Field listing the files to include, as a string or an array of strings.
+const INCLUDE_FIELD: &str = "$include";
+
+/// Expands `$include` directives in a document loaded from the loader key `key`.
+///
+/// Included paths are relative to the including file and are loaded through the
+/// same loader, so the sandbox of its source applies. The last path component may
+/// contain `*` and `?` wildcards; matches are included in name order. Included
+/// files are merged in the listed order and the including document is merged on
+/// top, so its own values win. Includes may be nested; cycles, missing and invalid
+/// included files fail with `LiveError::Include`.
+///
+/// `root` is the filesystem directory behind the loader key space, used to list
+/// wildcard matches and to report the canonical paths of included files, which are
+/// appended to `sources`.
+pub(crate) async fn expand(
+	loader: &DynLoader,
+	root: &Path,
+	key: &str,
+	document: Value,
+	sources: &mut Vec<PathBuf>,
+) -> Result<Value, LiveError> {
+	let mut stack = vec![normalize(key)];
+	expand_nested(loader, root, document, &mut stack, sources).await
+}
+
+fn expand_nested<'a>(
+	loader: &'a DynLoader,
+	root: &'a Path,
+	mut document: Value,
+	stack: &'a mut Vec<String>,
+	sources: &'a mut Vec<PathBuf>,
+) -> Pin<Box<dyn Future<Output = Result<Value, LiveError>> + Send + 'a>> {
+	Box::pin(async move {
+		let includes = match &mut document {
+			Value::Object(map) => match map.remove(INCLUDE_FIELD) {
+				Some(includes) => includes,
+				None => return Ok(document),
+			},
+			_ => return Ok(document),
+		};
+		let current = stack.last().cloned().unwrap_or_default();
+		let patterns = match includes {
+			Value::String(pattern) => vec![pattern],
+			Value::Array(items) => items
+				.into_iter()
+				.map(|item| match item {
+					Value::String(pattern) => Ok(pattern),
+					_ => Err(error(&current, "`$include` entries must be strings")),
+				})
+				.collect::<Result<_, _>>()?,
+			_ => return Err(error(&current, "`$include` must be a string or an array")),
+		};
+
+		let base_dir = Path::new(&current).parent().unwrap_or(Path::new(""));
+		let mut merged = Value::Null;
+		for pattern in patterns {
+			let pattern = normalize(&base_dir.join(&pattern).to_string_lossy());
+			for include in matches(root, &pattern, &current).await? {
+				if let Some(pos) = stack.iter().position(|k| *k == include) {
+					let mut cycle = stack[pos..].to_vec();
+					cycle.push(include);
+					return Err(error(
+						&current,
+						&format!("include cycle: {}", cycle.join(" -> ")),
+					));
+				}
+
+				let (included, info) = match loader.load_file::<Document>(&include).await {
+					LoadResult::Ok { value, info } => (value.0, info),
+					LoadResult::NotFound => {
+						return Err(error(
+							&current,
+							&format!("included file `{}` not found", include),
+						));
+					}
+					LoadResult::Invalid(e) => return Err(error(&include, &e.to_string())),
+				};
+				let path = root.join(&info.path);
+				sources.push(tokio::fs::canonicalize(&path).await.unwrap_or(path));
+
+				stack.push(include);
+				let included = expand_nested(loader, root, included, stack, sources).await?;
+				stack.pop();
+				merge(&mut merged, included);
+			}
+		}
+
+		merge(&mut merged, document);
+		Ok(merged)
+	})
+}
+
+fn error(key: &str, message: &str) -> LiveError {
+	LiveError::Include(format!("[{}] {}", key, message))
+}
+
+/// Resolves a pattern to loader keys, expanding wildcards in the last component.
+async fn matches(root: &Path, pattern: &str, current: &str) -> Result<Vec<String>, LiveError> {
+	let (dir, name) = match pattern.rsplit_once('/') {
+		Some((dir, name)) => (dir, name),
+		None => ("", pattern),
+	};
+	if !name.contains(['*', '?']) {
+		return Ok(vec![pattern.to_string()]);
+	}
+	if dir.contains(['*', '?']) {
+		return Err(error(
+			current,
+			&format!("wildcards are only supported in file names: `{}`", pattern),
+		));
+	}
+
+	let mut found = Vec::new();
+	// A missing directory simply matches nothing
+	if let Ok(mut entries) = tokio::fs::read_dir(root.join(dir)).await {
+		while let Ok(Some(entry)) = entries.next_entry().await {
+			let file_name = entry.file_name();
+			let file_name = file_name.to_string_lossy();
+			if !file_name.starts_with('.')
+				&& wildcard(name, &file_name)
+				&& entry.file_type().await.is_ok_and(|t| t.is_file())
+			{
+				found.push(if dir.is_empty() {
+					file_name.to_string()
+				} else {
+					format!("{}/{}", dir, file_name)
+				});
+			}
+		}
+	}
+	found.sort();
+	Ok(found)
+}
+
+/// Matches `name` against a pattern where `*` is any run of characters and `?`
+/// is a single character.
+fn wildcard(pattern: &str, name: &str) -> bool {
+	let pattern: Vec<char> = pattern.chars().collect();
+	let name: Vec<char> = name.chars().collect();
+	let (mut p, mut n) = (0, 0);
+	let mut backtrack: Option<(usize, usize)> = None;
+	while n < name.len() {
+		if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+			p += 1;
+			n += 1;
+		} else if p < pattern.len() && pattern[p] == '*' {
+			backtrack = Some((p, n));
+			p += 1;
+		} else if let Some((bp, bn)) = backtrack {
+			p = bp + 1;
+			n = bn + 1;
+			backtrack = Some((bp, bn + 1));
+		} else {
+			return false;
+		}
+	}
+	pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Lexically normalizes a loader key, folding `.` and `..` components.
+///
+/// Leading `..` components are kept so the source can reject them.
+fn normalize(key: &str) -> String {
+	let mut parts: Vec<&str> = Vec::new();
+	for component in Path::new(key).components() {
+		match component {
+			Component::Normal(part) => parts.push(part.to_str().unwrap_or_default()),
+			Component::ParentDir if parts.last().is_some_and(|p| *p != "..") => {
+				parts.pop();
+			}
+			Component::ParentDir => parts.push(".."),
+			_ => {}
+		}
+	}
+	parts.join("/")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_wildcard() {
+		assert!(wildcard("*.yaml", "cache.yaml"));
+		assert!(wildcard("cache-?.toml", "cache-1.toml"));
+		assert!(wildcard("*", "anything"));
+		assert!(!wildcard("*.yaml", "cache.toml"));
+		assert!(!wildcard("cache-?.toml", "cache-10.toml"));
+	}
+
+	#[test]
+	fn test_normalize() {
+		assert_eq!(normalize("conf/./db.toml"), "conf/db.toml");
+		assert_eq!(normalize("conf/../db.toml"), "db.toml");
+		assert_eq!(normalize("../secret.toml"), "../secret.toml");
+	}
+}
@@ -0,0 +1,213 @@
+/* src/controller/inherit.rs */
+
+//!
+//! `extends` inheritance between directory entries.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use super::document::merge;
+
+/// Inheritance between entries of a `LiveDir`.
+///
+/// An entry declaring `extends = "base-https"` is deep-merged over the entry (or
+/// template) with key `base-https`, which may itself extend another one. The
+/// `extends` field is removed before deserialization.
+///
+/// Directory entries whose name starts with the template prefix (default `_`) are
+/// templates: their key is extracted from the rest of the name with the regular
+/// `KeyPattern`, they can be extended, but they are never exposed as keys. A regular
+/// entry takes precedence over a template with the same key.
+#[derive(Debug, Clone)]
+pub struct Inheritance {
+	field: String,
+	template_prefix: Option<String>,
+}
+
+impl Default for Inheritance {
+	fn default() -> Self {
+		Self {
+			field: "extends".to_string(),
+			template_prefix: Some("_".to_string()),
+		}
+	}
+}
+
+impl Inheritance {
+	/// Creates inheritance using `field` to name the parent entry.
+	pub fn new(field: impl Into<String>) -> Self {
+		Self {
+			field: field.into(),
+			..Self::default()
+		}
+	}
+
+	/// Sets the name prefix marking template entries.
+	pub fn template_prefix(mut self, prefix: impl Into<String>) -> Self {
+		self.template_prefix = Some(prefix.into());
+		self
+	}
+
+	/// Disables templates; only regular entries can be extended.
+	pub fn no_templates(mut self) -> Self {
+		self.template_prefix = None;
+		self
+	}
+
+	/// Returns the remaining name if `name` denotes a template.
+	pub(crate) fn template_name<'a>(&self, name: &'a str) -> Option<&'a str> {
+		let prefix = self.template_prefix.as_deref()?;
+		name.strip_prefix(prefix).filter(|rest| !rest.is_empty())
+	}
+
+	/// Returns the parent key declared by `document`.
+	fn parent<'a>(&self, document: &'a Value) -> Result<Option<&'a str>, String> {
+		match document.get(&self.field) {
+			None | Some(Value::Null) => Ok(None),
+			Some(Value::String(parent)) => Ok(Some(parent)),
+			Some(_) => Err(format!("`{}` must be a string", self.field)),
+		}
+	}
+
+	/// Returns the keys `key` inherits from, nearest parent first.
+	///
+	/// Stops silently at missing parents and cycles; `resolve` reports those.
+	pub(crate) fn ancestors(
+		&self,
+		key: &str,
+		documents: &HashMap<String, Result<Value, String>>,
+	) -> Vec<String> {
+		let mut ancestors: Vec<String> = Vec::new();
+		let mut current = key;
+		while let Some(Ok(document)) = documents.get(current)
+			&& let Ok(Some(parent)) = self.parent(document)
+			&& parent != key
+			&& !ancestors.iter().any(|a| a == parent)
+		{
+			ancestors.push(parent.to_string());
+			current = parent;
+		}
+		ancestors
+	}
+
+	/// Resolves the inheritance chain of `key` and returns the merged document.
+	///
+	/// `documents` maps every loaded entry and template to its document, or to the
+	/// error that prevented it from loading.
+	pub(crate) fn resolve(
+		&self,
+		key: &str,
+		documents: &HashMap<String, Result<Value, String>>,
+	) -> Result<Value, String> {
+		let mut chain: Vec<&Value> = Vec::new();
+		let mut visited: Vec<&str> = vec![key];
+		let mut current = key;
+
+		loop {
+			let document = match documents.get(current) {
+				Some(Ok(document)) => document,
+				Some(Err(e)) => return Err(format!("parent `{}` failed to load: {}", current, e)),
+				None => return Err(format!("missing parent `{}`", current)),
+			};
+			chain.push(document);
+
+			let Some(parent) = self.parent(document)? else {
+				break;
+			};
+			if visited.contains(&parent) {
+				return Err(format!(
+					"inheritance cycle: {} -> {}",
+					visited.join(" -> "),
+					parent
+				));
+			}
+			visited.push(parent);
+			current = parent;
+		}
+
+		// Merge from the root ancestor down to the entry itself
+		let mut merged = Value::Null;
+		for document in chain.into_iter().rev() {
+			let mut document = document.clone();
+			if let Value::Object(map) = &mut document {
+				map.remove(&self.field);
+			}
+			merge(&mut merged, document);
+		}
+		Ok(merged)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	fn documents(list: &[(&str, Value)]) -> HashMap<String, Result<Value, String>> {
+		list
+			.iter()
+			.map(|(k, v)| (k.to_string(), Ok(v.clone())))
+			.collect()
+	}
+
+	#[test]
+	fn test_inheritance_chain() {
+		let inheritance = Inheritance::default();
+		let docs = documents(&[
+			("base", json!({"tls": false, "timeout": 30})),
+			("base-https", json!({"extends": "base", "tls": true})),
+			("443", json!({"extends": "base-https", "bind": ":443"})),
+		]);
+		assert_eq!(
+			inheritance.resolve("443", &docs).unwrap(),
+			json!({"tls": true, "timeout": 30, "bind": ":443"})
+		);
+	}
+
+	#[test]
+	fn test_inheritance_errors() {
+		let inheritance = Inheritance::default();
+		let mut docs = documents(&[
+			("a", json!({"extends": "b"})),
+			("b", json!({"extends": "a"})),
+			("c", json!({"extends": "missing"})),
+			("d", json!({"extends": 1})),
+			("e", json!({"extends": "broken"})),
+		]);
+		docs.insert("broken".to_string(), Err("parse error".to_string()));
+
+		let err = inheritance.resolve("a", &docs).unwrap_err();
+		assert_eq!(err, "inheritance cycle: a -> b -> a");
+		let err = inheritance.resolve("c", &docs).unwrap_err();
+		assert_eq!(err, "missing parent `missing`");
+		let err = inheritance.resolve("d", &docs).unwrap_err();
+		assert_eq!(err, "`extends` must be a string");
+		let err = inheritance.resolve("e", &docs).unwrap_err();
+		assert!(err.contains("parent `broken` failed to load"));
+	}
+
+	#[test]
+	fn test_ancestors() {
+		let inheritance = Inheritance::default();
+		let docs = documents(&[
+			("base", json!({})),
+			("https", json!({"extends": "base"})),
+			("443", json!({"extends": "https"})),
+			("a", json!({"extends": "b"})),
+			("b", json!({"extends": "a"})),
+		]);
+		assert_eq!(inheritance.ancestors("443", &docs), vec!["https", "base"]);
+		assert_eq!(inheritance.ancestors("a", &docs), vec!["b"]);
+		assert!(inheritance.ancestors("base", &docs).is_empty());
+	}
+
+	#[test]
+	fn test_template_name() {
+		let inheritance = Inheritance::default();
+		assert_eq!(inheritance.template_name("_base.json"), Some("base.json"));
+		assert_eq!(inheritance.template_name("_"), None);
+		assert_eq!(inheritance.template_name("app.json"), None);
+		assert_eq!(inheritance.no_templates().template_name("_base.json"), None);
+	}
+}
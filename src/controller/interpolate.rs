@@ -0,0 +1,337 @@
+/* src/controller/interpolate.rs */
+
+//!
+//! `${VAR}`, `${VAR:-default}` and `${file:path}` interpolation in loaded documents.
+
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+use fmtstruct::FmtError;
+use serde_json::Value;
+
+use super::LiveError;
+use super::write::check_sandbox;
+
+/// A parsed piece of a string value.
+enum Segment<'a> {
+	Text(&'a str),
+	Reference(Reference<'a>),
+}
+
+struct Reference<'a> {
+	/// The reference as written, e.g. `${PORT:-8080}`.
+	raw: &'a str,
+	source: Source<'a>,
+	default: Option<&'a str>,
+}
+
+enum Source<'a> {
+	Env(&'a str),
+	File(&'a str),
+}
+
+/// Splits a string into literal text and references.
+///
+/// `$${` escapes a literal `${`.
+fn parse(s: &str) -> Result<Vec<Segment<'_>>, String> {
+	let mut segments = Vec::new();
+	let mut rest = s;
+	while let Some(pos) = rest.find("${") {
+		if pos > 0 && rest[..pos].ends_with('$') {
+			segments.push(Segment::Text(&rest[..pos - 1]));
+			segments.push(Segment::Text("${"));
+			rest = &rest[pos + 2..];
+			continue;
+		}
+		segments.push(Segment::Text(&rest[..pos]));
+		let Some(end) = rest[pos..].find('}') else {
+			return Err(format!("unterminated reference in `{}`", s));
+		};
+		let raw = &rest[pos..pos + end + 1];
+		let inner = &raw[2..raw.len() - 1];
+		let (name, default) = match inner.split_once(":-") {
+			Some((name, default)) => (name, Some(default)),
+			None => (inner, None),
+		};
+		let source = match name.strip_prefix("file:") {
+			Some(path) => Source::File(path),
+			None => Source::Env(name),
+		};
+		segments.push(Segment::Reference(Reference {
+			raw,
+			source,
+			default,
+		}));
+		rest = &rest[pos + end + 1..];
+	}
+	segments.push(Segment::Text(rest));
+	segments.retain(|s| !matches!(s, Segment::Text("")));
+	Ok(segments)
+}
+
+/// Calls `f` with every string value in `document`.
+fn strings<'a>(document: &'a Value, f: &mut impl FnMut(&'a str)) {
+	match document {
+		Value::String(s) => f(s),
+		Value::Array(items) => items.iter().for_each(|v| strings(v, f)),
+		Value::Object(map) => map.values().for_each(|v| strings(v, f)),
+		_ => {}
+	}
+}
+
+/// Resolves all references in the string values of `document`.
+///
+/// Environment variables are read at call time. Relative file paths are resolved
+/// against `base_dir`, and file contents are inserted without trailing newlines.
+/// Files must lie within `root`, the directory behind the loader; references
+/// leaving it, including through symlinks, fail with `LiveError::Interpolate`.
+/// A string consisting of a single environment reference that resolves to a
+/// number or boolean becomes that scalar, so `port = "${PORT:-8080}"` can feed
+/// an integer field.
+///
+/// Returns the canonical paths of all referenced files, including missing ones
+/// in existing directories so they are seen when they appear, or
+/// `LiveError::Unresolved` for the first reference without value or default.
+pub(crate) async fn interpolate(
+	document: &mut Value,
+	root: &Path,
+	base_dir: &Path,
+	context: &str,
+) -> Result<Vec<PathBuf>, LiveError> {
+	// Referenced files are read up front so substitution stays synchronous
+	let mut paths = Vec::new();
+	let mut error = None;
+	strings(document, &mut |s| {
+		if !s.contains("${") {
+			return;
+		}
+		match parse(s) {
+			Ok(segments) => {
+				for segment in segments {
+					if let Segment::Reference(Reference {
+						source: Source::File(path),
+						..
+					}) = segment
+					{
+						paths.push(path.to_string());
+					}
+				}
+			}
+			Err(e) => {
+				error.get_or_insert(e);
+			}
+		}
+	});
+	if let Some(e) = error {
+		return Err(LiveError::Unresolved(format!("[{}] {}", context, e)));
+	}
+
+	let mut files: HashMap<String, Option<String>> = HashMap::new();
+	let mut watched = Vec::new();
+	let root = tokio::fs::canonicalize(root).await?;
+	for path in paths {
+		if files.contains_key(&path) {
+			continue;
+		}
+		let escapes = || {
+			LiveError::Interpolate(format!(
+				"[{}] `{}` is outside of {}",
+				context,
+				path,
+				root.display()
+			))
+		};
+		let full = normalize(&std::path::absolute(base_dir.join(&path))?);
+		let content = match tokio::fs::canonicalize(&full).await {
+			Ok(resolved) if !resolved.starts_with(&root) => return Err(escapes()),
+			Ok(resolved) => {
+				let content = tokio::fs::read_to_string(&resolved).await.ok();
+				watched.push(resolved);
+				content.map(|content| content.trim_end_matches(['\n', '\r']).to_string())
+			}
+			Err(_) => {
+				check_sandbox(&root, &full).await.map_err(|e| match e {
+					LiveError::Load(FmtError::SandboxViolation) => escapes(),
+					e => e,
+				})?;
+				// Watched in its directory until it appears
+				if let (Some(dir), Some(name)) = (full.parent(), full.file_name())
+					&& let Ok(dir) = tokio::fs::canonicalize(dir).await
+				{
+					watched.push(dir.join(name));
+				}
+				None
+			}
+		};
+		files.insert(path, content);
+	}
+
+	substitute(document, &files)
+		.map_err(|raw| LiveError::Unresolved(format!("[{}] {}", context, raw)))?;
+	Ok(watched)
+}
+
+/// Resolves `.` and `..` in an absolute path without touching the filesystem.
+fn normalize(path: &Path) -> PathBuf {
+	let mut normalized = PathBuf::new();
+	for component in path.components() {
+		match component {
+			Component::CurDir => {}
+			Component::ParentDir => {
+				normalized.pop();
+			}
+			component => normalized.push(component),
+		}
+	}
+	normalized
+}
+
+/// Replaces references in place, returning the first unresolved one.
+fn substitute(value: &mut Value, files: &HashMap<String, Option<String>>) -> Result<(), String> {
+	match value {
+		Value::String(s) if s.contains("${") => {
+			let segments = parse(s)?;
+			if let [Segment::Reference(reference)] = segments.as_slice()
+				&& let Source::Env(_) = reference.source
+			{
+				let resolved = resolve(reference, files)?;
+				*value = match serde_json::from_str::<Value>(&resolved) {
+					Ok(scalar @ (Value::Number(_) | Value::Bool(_))) => scalar,
+					_ => Value::String(resolved),
+				};
+				return Ok(());
+			}
+
+			let mut out = String::with_capacity(s.len());
+			for segment in &segments {
+				match segment {
+					Segment::Text(text) => out.push_str(text),
+					Segment::Reference(reference) => out.push_str(&resolve(reference, files)?),
+				}
+			}
+			*value = Value::String(out);
+		}
+		Value::Array(items) => {
+			for item in items {
+				substitute(item, files)?;
+			}
+		}
+		Value::Object(map) => {
+			for item in map.values_mut() {
+				substitute(item, files)?;
+			}
+		}
+		_ => {}
+	}
+	Ok(())
+}
+
+fn resolve(
+	reference: &Reference<'_>,
+	files: &HashMap<String, Option<String>>,
+) -> Result<String, String> {
+	let value = match reference.source {
+		Source::Env(name) => std::env::var(name).ok().filter(|v| !v.is_empty()),
+		Source::File(path) => files.get(path).cloned().flatten(),
+	};
+	value
+		.or_else(|| reference.default.map(String::from))
+		.ok_or_else(|| reference.raw.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+	use tokio::sync::Mutex;
+
+	/// Held by the tests that read or write the environment.
+	static ENV: Mutex<()> = Mutex::const_new(());
+
+	#[tokio::test]
+	async fn test_interpolate_env_and_defaults() {
+		let _env = ENV.lock().await;
+		// SAFETY: no other test reads or writes the environment while `ENV` is held.
+		unsafe { std::env::set_var("LIVE_INTERPOLATE_UNIT_HOST", "db.internal") };
+
+		let mut document = json!({
+			"url": "postgres://${LIVE_INTERPOLATE_UNIT_HOST}:${LIVE_INTERPOLATE_UNIT_PORT:-5432}/app",
+			"port": "${LIVE_INTERPOLATE_UNIT_PORT:-5432}",
+			"literal": "$${NOT_A_REFERENCE}",
+			"list": ["${LIVE_INTERPOLATE_UNIT_HOST}"],
+		});
+		let files = interpolate(&mut document, Path::new("."), Path::new("."), "app")
+			.await
+			.unwrap();
+
+		assert!(files.is_empty());
+		assert_eq!(
+			document,
+			json!({
+				"url": "postgres://db.internal:5432/app",
+				"port": 5432,
+				"literal": "${NOT_A_REFERENCE}",
+				"list": ["db.internal"],
+			})
+		);
+	}
+
+	#[tokio::test]
+	async fn test_interpolate_unresolved() {
+		let _env = ENV.lock().await;
+		let mut document = json!({"password": "${LIVE_INTERPOLATE_UNIT_MISSING}"});
+		let err = interpolate(&mut document, Path::new("."), Path::new("."), "app")
+			.await
+			.unwrap_err();
+		assert!(
+			matches!(err, LiveError::Unresolved(ref r) if r == "[app] ${LIVE_INTERPOLATE_UNIT_MISSING}")
+		);
+
+		let mut document = json!({"password": "${file:does/not/exist}"});
+		let err = interpolate(&mut document, Path::new("."), Path::new("."), "app")
+			.await
+			.unwrap_err();
+		assert!(matches!(err, LiveError::Unresolved(_)));
+	}
+
+	#[tokio::test]
+	async fn test_interpolate_files_within_root() {
+		let dir = tempfile::tempdir().unwrap();
+		let root = dir.path().join("conf");
+		std::fs::create_dir(&root).unwrap();
+		std::fs::write(dir.path().join("outside"), "secret").unwrap();
+		std::fs::write(root.join("inside"), "value\n").unwrap();
+
+		for reference in ["${file:../outside}", "${file:sub/../../outside}"] {
+			let mut document = json!({"password": reference});
+			let err = interpolate(&mut document, &root, &root, "app")
+				.await
+				.unwrap_err();
+			assert!(matches!(err, LiveError::Interpolate(_)), "{reference}");
+		}
+		let mut document =
+			json!({"password": format!("${{file:{}}}", dir.path().join("outside").display())});
+		let err = interpolate(&mut document, &root, &root, "app")
+			.await
+			.unwrap_err();
+		assert!(matches!(err, LiveError::Interpolate(_)));
+		#[cfg(unix)]
+		{
+			std::os::unix::fs::symlink(dir.path().join("outside"), root.join("link")).unwrap();
+			let mut document = json!({"password": "${file:link}"});
+			let err = interpolate(&mut document, &root, &root, "app")
+				.await
+				.unwrap_err();
+			assert!(matches!(err, LiveError::Interpolate(_)));
+		}
+
+		// Missing files are watched so they are seen when they appear
+		let mut document = json!({"password": "${file:./inside}", "token": "${file:token:-none}"});
+		let files = interpolate(&mut document, &root, &root, "app")
+			.await
+			.unwrap();
+		assert_eq!(document, json!({"password": "value", "token": "none"}));
+		let root = root.canonicalize().unwrap();
+		assert_eq!(files, vec![root.join("inside"), root.join("token")]);
+	}
+}
@@ -0,0 +1,220 @@
+/* src/controller/layer.rs */
+
+//!
+//! Layered configuration sources merged before deserialization.
+
+use std::path::{Path, PathBuf};
+
+use fmtstruct::{DynLoader, FmtError, LoadResult};
+use serde::Serialize;
+use serde_json::Value;
+
+use super::LiveError;
+use super::document::{Document, merge};
+use super::env::EnvOverrides;
+use super::include;
+use super::interpolate::interpolate;
+
+/// A single source in a layered configuration stack.
+///
+/// Layers are deep-merged in order: objects are merged key by key and any
+/// other value replaces what lower layers provided.
+#[derive(Debug, Clone)]
+pub enum Layer {
+	/// Inline values, e.g. compiled-in defaults or programmatic overrides.
+	Value(Value),
+	/// A file resolved by base name through the loader (probing all formats).
+	///
+	/// Missing files are skipped; invalid files fail the whole load.
+	File(String),
+	/// Environment variables, re-read on every load.
+	Env(EnvOverrides),
+}
+
+impl Layer {
+	/// Creates an inline layer from any serializable value.
+	pub fn value<S: Serialize>(value: &S) -> Result<Self, LiveError> {
+		serde_json::to_value(value)
+			.map(Self::Value)
+			.map_err(|e| LiveError::Builder(format!("invalid layer value: {}", e)))
+	}
+
+	/// Creates an optional file layer resolved by base name.
+	pub fn file(name: impl Into<String>) -> Self {
+		Self::File(name.into())
+	}
+}
+
+/// The merged result of a layer stack.
+pub(crate) struct Resolved {
+	pub document: Value,
+	/// Canonical path of the base file.
+	pub source: PathBuf,
+	/// Path of the base file relative to the loader's root.
+	pub file: PathBuf,
+	/// Canonical paths of every file that contributed to the document, including
+	/// files referenced through `${file:...}`.
+	pub sources: Vec<PathBuf>,
+}
+
+/// Ordered layers around a required base file.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LayerStack {
+	/// Layers merged beneath the base file.
+	pub below: Vec<Layer>,
+	/// Layers merged on top of the base file.
+	pub above: Vec<Layer>,
+	/// Profiles whose `<name>.<profile>` overlays are merged right above the base file.
+	pub profiles: Vec<String>,
+	/// Environment variable listing active profiles, taking precedence over `profiles`.
+	pub profile_env: Option<String>,
+	/// Resolve `${...}` references in the merged document.
+	pub interpolate: bool,
+}
+
+impl LayerStack {
+	/// Returns true if the stack only consists of the base file.
+	pub fn is_empty(&self) -> bool {
+		self.below.is_empty()
+			&& self.above.is_empty()
+			&& self.active_profiles().is_empty()
+			&& !self.interpolate
+	}
+
+	/// Returns the active profiles in merge order.
+	///
+	/// A non-empty `profile_env` variable (comma-separated) replaces the configured profiles.
+	pub fn active_profiles(&self) -> Vec<String> {
+		if let Some(var) = &self.profile_env
+			&& let Ok(value) = std::env::var(var)
+		{
+			let profiles: Vec<String> = value
+				.split(',')
+				.map(str::trim)
+				.filter(|p| !p.is_empty())
+				.map(String::from)
+				.collect();
+			if !profiles.is_empty() {
+				return profiles;
+			}
+		}
+		self.profiles.clone()
+	}
+
+	/// Returns the base names of the file layers and active profile overlays
+	/// around the base file `name`, which are skipped while missing.
+	#[cfg(feature = "signal")]
+	pub fn optional_files(&self, name: &str) -> Vec<String> {
+		let files = self
+			.below
+			.iter()
+			.chain(&self.above)
+			.filter_map(|layer| match layer {
+				Layer::File(file) => Some(file.clone()),
+				_ => None,
+			});
+		self
+			.active_profiles()
+			.into_iter()
+			.map(|profile| format!("{}.{}", name, profile))
+			.chain(files)
+			.collect()
+	}
+
+	/// Loads and merges all layers around the base file `name`.
+	///
+	/// `root` is the directory behind the loader key space, used to report the
+	/// canonical paths of the loaded files.
+	pub async fn resolve(
+		&self,
+		loader: &DynLoader,
+		root: &Path,
+		name: &str,
+	) -> Result<Resolved, LiveError> {
+		let mut document = Value::Null;
+		let mut sources = Vec::new();
+
+		for layer in &self.below {
+			Self::apply(layer, loader, root, &mut document, &mut sources).await?;
+		}
+
+		let (source, file) = match loader.load::<Document>(name).await {
+			LoadResult::Ok { value, info } => {
+				let path = canonical(root, &info.path).await;
+				sources.push(path.clone());
+				let value = Self::expand(loader, root, &info.path, value.0, &mut sources).await?;
+				merge(&mut document, value);
+				(path, info.path)
+			}
+			LoadResult::NotFound => return Err(LiveError::Load(FmtError::NotFound)),
+			LoadResult::Invalid(e) => return Err(LiveError::Load(e)),
+		};
+
+		for profile in self.active_profiles() {
+			let overlay = Layer::File(format!("{}.{}", name, profile));
+			Self::apply(&overlay, loader, root, &mut document, &mut sources).await?;
+		}
+
+		for layer in &self.above {
+			Self::apply(layer, loader, root, &mut document, &mut sources).await?;
+		}
+
+		if self.interpolate {
+			let base_dir = source.parent().unwrap_or(Path::new("."));
+			sources.extend(interpolate(&mut document, root, base_dir, name).await?);
+		}
+
+		Ok(Resolved {
+			document,
+			source,
+			file,
+			sources,
+		})
+	}
+
+	async fn apply(
+		layer: &Layer,
+		loader: &DynLoader,
+		root: &Path,
+		document: &mut Value,
+		sources: &mut Vec<PathBuf>,
+	) -> Result<(), LiveError> {
+		match layer {
+			Layer::Value(value) => merge(document, value.clone()),
+			Layer::File(name) => match loader.load::<Document>(name).await {
+				LoadResult::Ok { value, info } => {
+					sources.push(canonical(root, &info.path).await);
+					let value = Self::expand(loader, root, &info.path, value.0, sources).await?;
+					merge(document, value);
+				}
+				LoadResult::NotFound => {}
+				LoadResult::Invalid(e) => {
+					return Err(LiveError::Load(FmtError::ParseError(format!(
+						"[{}] {}",
+						name, e
+					))));
+				}
+			},
+			Layer::Env(env) => env.apply(document, None),
+		}
+		Ok(())
+	}
+
+	/// Expands `$include` directives of a file loaded from `path`.
+	async fn expand(
+		loader: &DynLoader,
+		root: &Path,
+		path: &Path,
+		document: Value,
+		sources: &mut Vec<PathBuf>,
+	) -> Result<Value, LiveError> {
+		let key = path.to_string_lossy();
+		include::expand(loader, root, &key, document, sources).await
+	}
+}
+
+/// Returns the canonical path of `path`, a file relative to `root`.
+pub(crate) async fn canonical(root: &Path, path: &Path) -> PathBuf {
+	let path = root.join(path);
+	tokio::fs::canonicalize(&path).await.unwrap_or(path)
+}
@@ -3,18 +3,35 @@
 //!
 //! Single-file configuration controller with live reloading.
 
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use atomhold::{Store, UnloadPolicy};
-use fmtstruct::{DynLoader, LoadResult, PreProcess, ValidateConfig};
+use fmtstruct::{DynLoader, FmtError, LoadResult, PreProcess, ValidateConfig};
+use serde::Serialize;
 use serde::de::DeserializeOwned;
+use serde_json::Value;
 
 #[cfg(feature = "signal")]
-use fsig::{Config as WatcherConfig, Target, Watcher};
+use fsig::{Config as WatcherConfig, Target};
 
 use super::LiveError;
+use super::apply::{ApplyFn, apply_fn, restore};
+use super::cache::{LastGood, LoadStatus};
+use super::diff::ConfigDiff;
+use super::document::{Document, content_hash, decode, patch};
+use super::edit;
+use super::env::EnvOverrides;
+use super::history::{DEFAULT_HISTORY_LIMIT, History, Revision};
+use super::layer::{Layer, LayerStack, canonical};
+use super::overrides::{Override, Overrides};
+use super::reload::{ReloadState, Reloads};
 #[cfg(feature = "signal")]
-use super::WatchState;
+use super::watch::{Change, Rescan, WatchSet, base_target, is_base_file, pending_target};
+use super::write::{Writes, check_sandbox, serialize};
+#[cfg(feature = "signal")]
+use super::{WatchState, WatchStatus};
 
 /// A controller for a live-reloading configuration value.
 ///
@@ -25,21 +42,55 @@ use super::WatchState;
 /// stopped and the background task aborted when the last remaining instance is dropped
 /// or when `stop_watching` is called on the last instance holding the active watcher.
 pub struct Live<T> {
+	inner: Arc<LiveInner<T>>,
+	#[cfg(feature = "signal")]
+	watch_state: Option<Arc<WatchState>>,
+}
+
+/// State shared by all clones of a `Live` and its watch task.
+struct LiveInner<T> {
 	store: Arc<Store<T>>,
 	loader: Arc<DynLoader>,
 	key: String,
+	root: Option<PathBuf>,
+	layers: LayerStack,
+	state: Mutex<LoadState>,
+	equal: Option<EqualFn<T>>,
+	on_apply: Option<ApplyFn<T>>,
+	history: Mutex<History<T>>,
+	last_good: Option<LastGood>,
+	overrides: Arc<Overrides<T>>,
+	writes: Writes,
+	reloads: Reloads<ConfigDiff>,
 	on_error: Option<Arc<dyn Fn(LiveError) + Send + Sync>>,
 	#[cfg(feature = "signal")]
-	watch_state: Option<Arc<WatchState>>,
+	rescan_timer: Option<Rescan>,
+	#[cfg(feature = "signal")]
+	poll_interval: Option<Duration>,
+}
+
+/// Compares the current and the newly loaded value.
+pub(crate) type EqualFn<T> = Arc<dyn Fn(&T, &T) -> bool + Send + Sync>;
+
+/// What the last successful load was built from.
+#[derive(Default)]
+struct LoadState {
+	/// Files that contributed to the merged configuration.
+	sources: Vec<PathBuf>,
+	/// The resolved base file, whose directory is watched.
+	source: Option<PathBuf>,
+	/// The merged document, diffed against on the next load.
+	document: Option<Value>,
+	/// Content hash of the merged document.
+	hash: Option<u64>,
+	/// The base file relative to the loader's root, written by `update`.
+	file: Option<PathBuf>,
 }
 
 impl<T> Clone for Live<T> {
 	fn clone(&self) -> Self {
 		Self {
-			store: self.store.clone(),
-			loader: self.loader.clone(),
-			key: self.key.clone(),
-			on_error: self.on_error.clone(),
+			inner: self.inner.clone(),
 			#[cfg(feature = "signal")]
 			watch_state: self.watch_state.clone(),
 		}
@@ -58,7 +109,20 @@ pub struct LiveBuilder<T> {
 	store: Option<Arc<Store<T>>>,
 	loader: Option<Arc<DynLoader>>,
 	key: Option<String>,
+	root: Option<PathBuf>,
+	layers: LayerStack,
+	layer_error: Option<LiveError>,
+	equal: Option<EqualFn<T>>,
+	on_apply: Option<ApplyFn<T>>,
+	history_limit: usize,
+	last_good: Option<LastGood>,
 	on_error: Option<Arc<dyn Fn(LiveError) + Send + Sync>>,
+	#[cfg(feature = "signal")]
+	rescan_interval: Option<Duration>,
+	#[cfg(feature = "signal")]
+	rescan_jitter: Duration,
+	#[cfg(feature = "signal")]
+	poll_interval: Option<Duration>,
 }
 
 impl<T> LiveBuilder<T>
@@ -70,7 +134,20 @@ where
 			store: None,
 			loader: None,
 			key: None,
+			root: None,
+			layers: LayerStack::default(),
+			layer_error: None,
+			equal: None,
+			on_apply: None,
+			history_limit: DEFAULT_HISTORY_LIMIT,
+			last_good: None,
 			on_error: None,
+			#[cfg(feature = "signal")]
+			rescan_interval: None,
+			#[cfg(feature = "signal")]
+			rescan_jitter: Duration::ZERO,
+			#[cfg(feature = "signal")]
+			poll_interval: None,
 		}
 	}
 
@@ -89,6 +166,152 @@ where
 		self
 	}
 
+	/// Sets the directory the loader's `FileSource` is rooted at.
+	///
+	/// Watching and the paths reported for loaded files resolve against it, the
+	/// working directory by default. Required by [`Live::update`] and
+	/// [`Live::set_field`], which write the base file within it.
+	pub fn root(mut self, root: impl Into<PathBuf>) -> Self {
+		self.root = Some(root.into());
+		self
+	}
+
+	/// Adds compiled-in defaults merged beneath the base file.
+	///
+	/// May be called multiple times; later defaults take precedence over earlier ones.
+	pub fn defaults<S: Serialize>(mut self, value: &S) -> Self {
+		match Layer::value(value) {
+			Ok(layer) => self.layers.below.push(layer),
+			Err(e) => self.layer_error = Some(e),
+		}
+		self
+	}
+
+	/// Adds a layer merged on top of the base file.
+	///
+	/// Layers are applied in the order they are added, so the last one wins.
+	pub fn layer(mut self, layer: Layer) -> Self {
+		self.layers.above.push(layer);
+		self
+	}
+
+	/// Activates a profile: `<key>.<profile>` (any supported format) is merged
+	/// on top of the base file when present.
+	///
+	/// May be called multiple times; later profiles take precedence.
+	pub fn profile(mut self, profile: impl Into<String>) -> Self {
+		self.layers.profiles.push(profile.into());
+		self
+	}
+
+	/// Reads the active profiles (comma-separated) from an environment variable.
+	///
+	/// When the variable is set and non-empty it replaces the profiles given via
+	/// [`profile`](Self::profile). It is re-read on every load.
+	pub fn profile_env(mut self, var: impl Into<String>) -> Self {
+		self.layers.profile_env = Some(var.into());
+		self
+	}
+
+	/// Adds an environment variable layer on top of all previously added layers.
+	pub fn env(mut self, env: EnvOverrides) -> Self {
+		self.layers.above.push(Layer::Env(env));
+		self
+	}
+
+	/// Resolves `${VAR}`, `${VAR:-default}` and `${file:path}` references in string
+	/// values of the merged document.
+	///
+	/// Relative file paths are resolved against the directory of the base file's
+	/// source path (relative to the working directory for relative loader roots),
+	/// and referenced files are watched, also while missing. Files outside of
+	/// [`root`](Self::root) fail the load with `LiveError::Interpolate`, and
+	/// unresolved references with `LiveError::Unresolved`.
+	pub fn interpolate(mut self, enabled: bool) -> Self {
+		self.layers.interpolate = enabled;
+		self
+	}
+
+	/// Also skips reloads whose decoded value equals the current one.
+	///
+	/// Reloads of byte-identical content are always skipped; this additionally
+	/// catches changes that do not affect `T`, such as reordered keys or fields
+	/// `T` ignores.
+	pub fn skip_equal(mut self) -> Self
+	where
+		T: PartialEq,
+	{
+		self.equal = Some(Arc::new(|a: &T, b: &T| a == b));
+		self
+	}
+
+	/// Hands every changed value to the application after it passed validation.
+	///
+	/// The hook receives the previous value (if any) and the new one, which is
+	/// already in the store. If it fails, the previous value is restored and the
+	/// reload fails with `LiveError::RolledBack`. An override or rollback made
+	/// while the hook ran stays in place.
+	pub fn on_apply<F, Fut, E>(mut self, f: F) -> Self
+	where
+		F: Fn(Option<Arc<T>>, Arc<T>) -> Fut + Send + Sync + 'static,
+		Fut: Future<Output = Result<(), E>> + Send + 'static,
+		E: std::fmt::Display,
+	{
+		self.on_apply = Some(apply_fn(f));
+		self
+	}
+
+	/// Sets how many previous values are kept for `history()` and `rollback_to()`.
+	///
+	/// Defaults to 10. Zero disables the history.
+	pub fn history_limit(mut self, limit: usize) -> Self {
+		self.history_limit = limit;
+		self
+	}
+
+	/// Keeps the last successfully loaded value as `<key>.<ext>` in `dir`, in
+	/// the format of the base file.
+	///
+	/// If the sources are invalid or missing when `load()` runs, the cached value
+	/// is used instead and `LoadStatus::Cached` is returned. The cache holds the
+	/// merged document, including environment overrides and interpolated values.
+	/// Values of non self-describing formats (e.g. postcard) are not cached.
+	/// Failures to write the cache are reported through `on_error`.
+	pub fn last_good(mut self, dir: impl Into<PathBuf>) -> Self {
+		self.last_good = Some(LastGood::new(dir.into()));
+		self
+	}
+
+	/// Reloads everything every `interval` while watching, in addition to
+	/// reacting to events, as a safety net for filesystems that lose them (e.g.
+	/// overlay or network mounts). Results are reported like those of any other
+	/// reload.
+	#[cfg(feature = "signal")]
+	pub fn rescan_interval(mut self, interval: Duration) -> Self {
+		self.rescan_interval = Some(interval);
+		self
+	}
+
+	/// Delays each periodic rescan by a random amount of up to `jitter`, so
+	/// controllers started together do not rescan in lockstep.
+	#[cfg(feature = "signal")]
+	pub fn rescan_jitter(mut self, jitter: Duration) -> Self {
+		self.rescan_jitter = jitter;
+		self
+	}
+
+	/// Detects changes by polling every `interval` instead of through filesystem
+	/// events, for filesystems where these never arrive (e.g. network mounts).
+	///
+	/// Files are compared by modification time, size and content. Without this,
+	/// polling is only used for targets whose watcher cannot be created (e.g.
+	/// at the inotify watch limit), which is reported through `on_error`.
+	#[cfg(feature = "signal")]
+	pub fn poll_interval(mut self, interval: Duration) -> Self {
+		self.poll_interval = Some(interval);
+		self
+	}
+
 	pub fn on_error<F>(mut self, f: F) -> Self
 	where
 		F: Fn(LiveError) + Send + Sync + 'static,
@@ -98,6 +321,9 @@ where
 	}
 
 	pub fn build(self) -> Result<Live<T>, LiveError> {
+		if let Some(e) = self.layer_error {
+			return Err(e);
+		}
 		let store = self
 			.store
 			.ok_or_else(|| LiveError::Builder("store is required".to_string()))?;
@@ -107,12 +333,46 @@ where
 		let key = self
 			.key
 			.ok_or_else(|| LiveError::Builder("key is required".to_string()))?;
+		#[cfg(feature = "signal")]
+		let rescan = match self.rescan_interval {
+			Some(interval) if interval.is_zero() => {
+				return Err(LiveError::Builder(
+					"rescan interval must not be zero".to_string(),
+				));
+			}
+			interval => interval.map(|interval| Rescan::new(interval, self.rescan_jitter)),
+		};
+		#[cfg(feature = "signal")]
+		if self
+			.poll_interval
+			.is_some_and(|interval| interval.is_zero())
+		{
+			return Err(LiveError::Builder(
+				"poll interval must not be zero".to_string(),
+			));
+		}
 
 		Ok(Live {
-			store,
-			loader,
-			key,
-			on_error: self.on_error,
+			inner: Arc::new(LiveInner {
+				store,
+				loader,
+				key,
+				root: self.root,
+				layers: self.layers,
+				state: Mutex::new(LoadState::default()),
+				equal: self.equal,
+				on_apply: self.on_apply,
+				history: Mutex::new(History::new(self.history_limit)),
+				last_good: self.last_good,
+				overrides: Arc::new(Overrides::new()),
+				writes: Writes::default(),
+				reloads: Reloads::new(),
+				on_error: self.on_error,
+				#[cfg(feature = "signal")]
+				rescan_timer: rescan,
+				#[cfg(feature = "signal")]
+				poll_interval: self.poll_interval,
+			}),
 			#[cfg(feature = "signal")]
 			watch_state: None,
 		})
@@ -141,7 +401,6 @@ impl<T> Live<T> {
 			if let Some(state) = self.watch_state.take()
 				&& let Ok(state) = Arc::try_unwrap(state)
 			{
-				state.watcher.stop();
 				state.abort_handle.abort();
 			}
 		}
@@ -152,6 +411,14 @@ impl<T> Live<T> {
 	pub fn is_watching(&self) -> bool {
 		self.watch_state.is_some()
 	}
+
+	/// Returns the health of the watcher, or `None` if not watching.
+	///
+	/// Transitions are also reported through `on_error`.
+	#[cfg(feature = "signal")]
+	pub fn watch_status(&self) -> Option<WatchStatus> {
+		self.watch_state.as_ref().map(|state| state.status())
+	}
 }
 
 impl<T> Live<T>
@@ -164,113 +431,270 @@ where
 
 	pub fn new(store: Arc<Store<T>>, loader: DynLoader, key: impl Into<String>) -> Self {
 		Self {
-			store,
-			loader: Arc::new(loader),
-			key: key.into(),
-			on_error: None,
+			inner: Arc::new(LiveInner {
+				store,
+				loader: Arc::new(loader),
+				key: key.into(),
+				root: None,
+				layers: LayerStack::default(),
+				state: Mutex::new(LoadState::default()),
+				equal: None,
+				on_apply: None,
+				history: Mutex::new(History::new(DEFAULT_HISTORY_LIMIT)),
+				last_good: None,
+				overrides: Arc::new(Overrides::new()),
+				writes: Writes::default(),
+				reloads: Reloads::new(),
+				on_error: None,
+				#[cfg(feature = "signal")]
+				rescan_timer: None,
+				#[cfg(feature = "signal")]
+				poll_interval: None,
+			}),
 			#[cfg(feature = "signal")]
 			watch_state: None,
 		}
 	}
 
 	/// Performs an immediate load from the source.
-	pub async fn load(&self) -> Result<(), LiveError> {
-		match self.loader.load::<T>(&self.key).await {
-			LoadResult::Ok { mut value, info } => {
-				value.set_context(&self.key);
-				if let Err(e) = value.validate_config() {
-					return Err(LiveError::Load(e));
-				}
-				let source_path = tokio::fs::canonicalize(&info.path)
-					.await
-					.unwrap_or(info.path);
-
-				self.store.insert(
-					self.key.clone(),
-					value,
-					source_path,
-					UnloadPolicy::default(),
-				);
-				Ok(())
-			}
-			LoadResult::NotFound => Err(LiveError::Load(fmtstruct::FmtError::NotFound)),
-			LoadResult::Invalid(e) => Err(LiveError::Load(e)),
+	///
+	/// All configured layers are merged around the base file before the result
+	/// is deserialized, validated and stored.
+	///
+	/// With `last_good` configured, a failed initial load falls back to the cached
+	/// value, which is flagged in the returned status.
+	pub async fn load(&self) -> Result<LoadStatus, LiveError> {
+		match self.reload().await {
+			Ok(_) => Ok(LoadStatus::Source),
+			Err(e) => self.inner.load_cached(e).await,
 		}
 	}
 
 	/// Manually reloads the configuration.
-	pub async fn reload(&self) -> Result<(), LiveError> {
-		self.load().await
+	///
+	/// Returns the field-level difference to the previously loaded configuration.
+	/// If nothing changed, the store is left untouched and the diff is empty.
+	///
+	/// Reloads run one at a time. Calls made while one runs share a single
+	/// follow-up reload, whose diff they all return.
+	pub async fn reload(&self) -> Result<ConfigDiff, LiveError> {
+		self.inner.load().await
+	}
+
+	/// Returns whether a reload is running or waiting to run.
+	pub fn reload_state(&self) -> ReloadState {
+		self.inner.reloads.state()
 	}
 
 	/// Returns the current configuration value.
 	pub fn get(&self) -> Option<Arc<T>> {
-		self.store.get(&self.key)
+		self.inner.store.get(&self.inner.key)
+	}
+
+	/// Returns the generation of the current value, or 0 before the first load.
+	///
+	/// Every reload that changes the value (and every rollback) starts a new
+	/// generation.
+	pub fn current_generation(&self) -> u64 {
+		self.inner.history().current()
+	}
+
+	/// Returns the recorded previous and current values, oldest first.
+	pub fn history(&self) -> Vec<Revision<T>> {
+		self.inner.history().list(&self.inner.key)
+	}
+
+	/// Restores the value that was current at `generation`, without touching files.
+	///
+	/// The restored value becomes a new generation and stays in place until the
+	/// underlying files change. `on_apply` is not run. Returns false if the value
+	/// already matched or is overridden.
+	pub fn rollback_to(&self, generation: u64) -> Result<bool, LiveError> {
+		let inner = &self.inner;
+		let restored = inner
+			.history()
+			.rollback(&inner.store, [&inner.key], generation, |key| {
+				inner.overrides.contains(key)
+			})?;
+		Ok(!restored.is_empty())
+	}
+
+	/// Pins `value` in the store above whatever the files say.
+	///
+	/// The value is validated first. While it is in place, reloads still load
+	/// and validate the files but hold their value back until the override is
+	/// cleared. `on_apply` is not run and no generation is recorded.
+	pub fn set_override(&self, value: T) -> Result<(), LiveError> {
+		let inner = &self.inner;
+		let value = inner.validate(value)?;
+		inner.overrides.set(&inner.store, &inner.key, value, None);
+		Ok(())
+	}
+
+	/// Like [`set_override`](Self::set_override), but restores the file value
+	/// after `ttl`.
+	///
+	/// Must be called within a Tokio runtime.
+	pub fn set_override_for(&self, value: T, ttl: Duration) -> Result<(), LiveError> {
+		let inner = &self.inner;
+		let value = inner.validate(value)?;
+		inner
+			.overrides
+			.set_for(&inner.store, &inner.key, value, ttl);
+		Ok(())
+	}
+
+	/// Returns the active override, if any.
+	pub fn get_override(&self) -> Option<Override<T>> {
+		self.inner.overrides.list().pop()
+	}
+
+	/// Ends the override and publishes the latest file value again.
+	///
+	/// Returns false if there was no override.
+	pub fn clear_override(&self) -> bool {
+		let inner = &self.inner;
+		inner.overrides.clear(&inner.store, &inner.key)
+	}
+
+	/// Applies `f` to a copy of the current value and writes the changes back to
+	/// the base file.
+	///
+	/// The new value is validated and compared to the current one. Only the
+	/// fields `f` changed are set in the base file's own document, so values
+	/// from defaults, layers, profiles or the environment and unresolved
+	/// `${...}` references stay where they are. The document is serialized in
+	/// the format of the base file and written atomically (temp file and rename)
+	/// next to it, then loaded like any other change. The watch event caused by
+	/// the write does not trigger another reload.
+	///
+	/// A field that a higher layer also sets keeps that layer's value after
+	/// the reload.
+	///
+	/// Requires [`root`](LiveBuilder::root), within which the file is written.
+	/// Fails with `LiveError::NotLoaded` until a value was loaded from the base
+	/// file.
+	pub async fn update<F>(&self, f: F) -> Result<ConfigDiff, LiveError>
+	where
+		F: FnOnce(&mut T),
+		T: Serialize,
+	{
+		let inner = &self.inner;
+		let _guard = inner.writes.lock().await;
+		let path = inner.base_file().await?;
+		// Overrides are not written, the file value underneath them is
+		let current = inner
+			.overrides
+			.file_entry(&inner.store, &inner.key)
+			.ok_or(LiveError::NotLoaded)?;
+		let mut value = (*current.value).clone();
+		f(&mut value);
+		let value = inner.validate(value)?;
+		let to_value = |value: &T| {
+			serde_json::to_value(value).map_err(|e| LiveError::Write(format!("{}: {}", inner.key, e)))
+		};
+		let (before, after) = (to_value(&current.value)?, to_value(&value)?);
+		let mut document = inner.base_document().await?;
+		patch(&mut document, &before, &after);
+		let bytes = serialize(&document, &path)?;
+		inner.writes.write(&path, &bytes).await?;
+		inner.load().await
+	}
+
+	/// Changes the single field at `path` (e.g. `db.port` or `listeners[0].bind`)
+	/// in the base file, keeping its comments and layout.
+	///
+	/// Only the text of the field's value is replaced. JSON and TOML fields can
+	/// be edited at any path, YAML fields only if they hold a scalar within
+	/// block mappings. The field must exist already. The edited file is then
+	/// loaded like any other change; if that fails, the previous content is
+	/// written back and the error is returned.
+	///
+	/// Requires [`root`](LiveBuilder::root), like [`update`](Self::update).
+	pub async fn set_field<V>(&self, path: &str, value: V) -> Result<ConfigDiff, LiveError>
+	where
+		V: Serialize,
+	{
+		let inner = &self.inner;
+		let _guard = inner.writes.lock().await;
+		let file = inner.base_file().await?;
+		let value =
+			serde_json::to_value(value).map_err(|e| LiveError::Write(format!("{}: {}", path, e)))?;
+		let original = tokio::fs::read_to_string(&file).await?;
+		let edited = edit::set_field(&original, &file, path, &value)?;
+		inner.writes.write(&file, edited.as_bytes()).await?;
+		match inner.load().await {
+			Ok(diff) => Ok(diff),
+			Err(e) => {
+				// A failed load leaves the current value in place
+				inner.writes.write(&file, original.as_bytes()).await?;
+				Err(e)
+			}
+		}
 	}
 
 	/// Subscribes to store change events.
 	#[cfg(feature = "events")]
 	pub fn subscribe(&self) -> tokio::sync::broadcast::Receiver<atomhold::HoldEvent<T>> {
-		self.store.subscribe()
+		self.inner.store.subscribe()
 	}
 
 	/// Attaches a filesystem watcher for live reloading (borrowing version).
 	///
-	/// Must call `load()` before `start_watching()` to establish the source path.
+	/// Every file that contributed to the merged configuration is watched. The
+	/// base file is watched through its directory, so it is followed when it is
+	/// replaced by a rename, deleted and recreated, or switched to another format
+	/// (e.g. from `app.json` to `app.toml`).
+	///
+	/// Watching may start before the base file (or its directory) exists, in
+	/// which case it is loaded as soon as it appears. Its location is derived
+	/// from [`root`](LiveBuilder::root) until it was loaded once.
 	#[cfg(feature = "signal")]
 	pub async fn start_watching(&mut self, config: WatcherConfig) -> Result<(), LiveError> {
-		let meta = self.store.get_meta(&self.key).ok_or(LiveError::NotLoaded)?;
-		let watch_path = meta.source;
-
-		let target = Target::File(watch_path);
-		let watcher = Watcher::new(target, config)?;
+		let mut watch_set = WatchSet::new(config, self.inner.rescan_timer, self.inner.poll_interval);
+		watch_set.sync(self.inner.watch_targets())?;
+		let status = watch_set.status();
 
-		let mut rx = watcher.subscribe();
-		let store = self.store.clone();
-		let loader = self.loader.clone();
-		let key = self.key.clone();
-		let on_error = self.on_error.clone();
+		let inner = self.inner.clone();
 
 		let handle = tokio::spawn(async move {
-			while let Ok(_event) = rx.recv().await {
-				match loader.load::<T>(&key).await {
-					LoadResult::Ok { mut value, info } => {
-						value.set_context(&key);
-						if let Err(e) = value.validate_config() {
-							if let Some(ref cb) = on_error {
-								cb(LiveError::Load(e));
-							}
+			// The file may have appeared before the watcher was set up
+			if inner.state().source.is_none() {
+				inner.refresh(&mut watch_set).await;
+			}
+			loop {
+				let change = watch_set.next().await;
+				if let Some(e) = change.error()
+					&& let Some(ref cb) = inner.on_error
+				{
+					cb(e);
+				}
+				match change {
+					Change::Events(paths) => {
+						if !paths.iter().any(|path| inner.is_watched(path)) {
 							continue;
 						}
-						let source_path = tokio::fs::canonicalize(&info.path)
-							.await
-							.unwrap_or(info.path);
-						store.insert(key.clone(), value, source_path, UnloadPolicy::default());
-					}
-					LoadResult::Invalid(e) => {
-						if let Some(ref cb) = on_error {
-							cb(LiveError::Load(e));
-						}
-					}
-					LoadResult::NotFound => {
-						if let Some(ref cb) = on_error {
-							cb(LiveError::Load(fmtstruct::FmtError::NotFound));
+						// Writes made by `update` are loaded already
+						if inner.writes.all_own(&paths).await {
+							continue;
 						}
 					}
+					// Changes may have been missed
+					Change::Lagged(_) | Change::Restored(_) | Change::Rescan => {}
+					Change::Failed(_) | Change::Fallback(_) => continue,
 				}
+				inner.refresh(&mut watch_set).await;
 			}
 		});
 
 		self.watch_state = Some(Arc::new(WatchState {
-			watcher,
 			abort_handle: handle.abort_handle(),
+			status,
 		}));
 		Ok(())
 	}
 
 	/// Attaches a filesystem watcher for live reloading (consuming version).
-	///
-	/// Must call `load()` before `watch()` to establish the source path.
 	#[cfg(feature = "signal")]
 	pub async fn watch(mut self, config: WatcherConfig) -> Result<Self, LiveError> {
 		self.start_watching(config).await?;
@@ -278,15 +702,326 @@ where
 	}
 }
 
+impl<T> LiveInner<T>
+where
+	T: Clone + Send + Sync + DeserializeOwned + PreProcess + ValidateConfig + 'static,
+{
+	/// Performs a load once no other one runs (used by load, reload, watch and
+	/// write-back).
+	async fn load(&self) -> Result<ConfigDiff, LiveError> {
+		self
+			.reloads
+			.run(true, async { self.load_now().await.map(|diff| (diff, ())) })
+			.await
+			.map(|(diff, ())| diff)
+	}
+
+	async fn load_now(&self) -> Result<ConfigDiff, LiveError> {
+		let (store, loader, key, layers) = (&self.store, &self.loader, &self.key, &self.layers);
+		let (value, source, file, loaded_from, document) =
+			match layers.resolve(loader, self.root(), key).await {
+				Ok(resolved) => {
+					let document = resolved.document.clone();
+					let value = decode::<T>(resolved.document, key)?;
+					let (source, file) = (resolved.source, resolved.file);
+					(value, source, file, resolved.sources, Some(document))
+				}
+				// Non self-describing formats (e.g. postcard) cannot be loaded as an
+				// untyped document, so a lone base file is deserialized directly.
+				Err(LiveError::Load(e)) if layers.is_empty() && !matches!(e, FmtError::NotFound) => {
+					match loader.load::<T>(key).await {
+						LoadResult::Ok { mut value, info } => {
+							value.set_context(key);
+							value.validate_config()?;
+							let source = canonical(self.root(), &info.path).await;
+							(value, source.clone(), info.path, vec![source], None)
+						}
+						_ => return Err(LiveError::Load(e)),
+					}
+				}
+				Err(e) => return Err(e),
+			};
+
+		let hash = document.as_ref().map(content_hash);
+		let previous = self.overrides.file_entry(store, key);
+		let (unchanged, diff) = {
+			let state = self.state();
+
+			// Skip no-op reloads so subscribers only see real changes
+			let unchanged = previous.as_ref().is_some_and(|current| {
+				current.meta.source == source
+					&& ((hash.is_some() && hash == state.hash)
+						|| self
+							.equal
+							.as_ref()
+							.is_some_and(|eq| eq(&current.value, &value)))
+			});
+
+			let diff = match (&document, unchanged) {
+				(Some(new), false) => {
+					ConfigDiff::between(state.document.as_ref().unwrap_or(&Value::Null), new)
+				}
+				_ => ConfigDiff::default(),
+			};
+			(unchanged, diff)
+		};
+		let source_path = source.clone();
+		// An override keeps the new value from being published
+		let publish = if unchanged {
+			None
+		} else {
+			self
+				.overrides
+				.hold(key, value, source, UnloadPolicy::default())
+		};
+		if let Some((value, source)) = publish {
+			let new = store.insert(
+				key.to_string(),
+				value,
+				source.clone(),
+				UnloadPolicy::default(),
+			);
+			if let Some(on_apply) = &self.on_apply {
+				let old = previous.as_ref().map(|entry| Arc::clone(&entry.value));
+				if let Err(e) = on_apply(old, Arc::clone(&new)).await {
+					// The state still describes the restored value
+					self.overrides.restore(store, key, &new, previous);
+					return Err(LiveError::RolledBack(format!("[{}] {}", key, e)));
+				}
+			}
+			{
+				let mut history = self.history();
+				let generation = history.advance();
+				history.record(key, generation, new, source, hash);
+			}
+			if let (Some(last_good), Some(document)) = (&self.last_good, &document)
+				&& let Err(e) = last_good.save(key, document, &source_path).await
+				&& let Some(ref cb) = self.on_error
+			{
+				cb(e);
+			}
+		}
+
+		let mut state = self.state();
+		state.sources = loaded_from;
+		state.source = Some(source_path);
+		state.document = document;
+		state.hash = hash;
+		state.file = Some(file);
+		Ok(diff)
+	}
+
+	/// Falls back to the last-known-good value after the initial load failed.
+	///
+	/// Returns `error` if there is no usable cached value.
+	async fn load_cached(&self, error: LiveError) -> Result<LoadStatus, LiveError> {
+		let (store, key) = (&self.store, &self.key);
+		let Some(last_good) = &self.last_good else {
+			return Err(error);
+		};
+		// A value that is already loaded stays in place
+		if store.get(key).is_some() {
+			return Err(error);
+		}
+		let Ok(Some((value, path))) = last_good.load::<T>(key).await else {
+			return Err(error);
+		};
+		let new = store.insert(key.clone(), value, path, UnloadPolicy::default());
+		if let Some(on_apply) = &self.on_apply
+			&& on_apply(None, new).await.is_err()
+		{
+			restore(store, key, None);
+			return Err(error);
+		}
+		Ok(LoadStatus::Cached {
+			reason: error.to_string(),
+		})
+	}
+
+	/// Returns the base file for write-back, checked to be within the root.
+	async fn base_file(&self) -> Result<PathBuf, LiveError> {
+		let root = self.root.as_ref().ok_or_else(|| {
+			LiveError::Write("write-back requires the loader root, see LiveBuilder::root".to_string())
+		})?;
+		let file = self.state().file.clone().ok_or(LiveError::NotLoaded)?;
+		let path = root.join(file);
+		check_sandbox(root, &path).await?;
+		Ok(path)
+	}
+
+	/// Loads the base file on its own, before includes, layers and interpolation.
+	async fn base_document(&self) -> Result<Value, LiveError> {
+		let file = self.state().file.clone().ok_or(LiveError::NotLoaded)?;
+		match self
+			.loader
+			.load_file::<Document>(&file.to_string_lossy())
+			.await
+		{
+			LoadResult::Ok { value, .. } => Ok(value.0),
+			LoadResult::NotFound => Err(LiveError::Load(FmtError::NotFound)),
+			LoadResult::Invalid(e) => Err(LiveError::Load(e)),
+		}
+	}
+
+	/// Returns the loader root, or the working directory if none is set.
+	fn root(&self) -> &Path {
+		self.root.as_deref().unwrap_or(Path::new("."))
+	}
+
+	fn state(&self) -> std::sync::MutexGuard<'_, LoadState> {
+		self.state.lock().unwrap_or_else(|e| e.into_inner())
+	}
+
+	fn history(&self) -> std::sync::MutexGuard<'_, History<T>> {
+		self.history.lock().unwrap_or_else(|e| e.into_inner())
+	}
+
+	fn validate(&self, mut value: T) -> Result<T, LiveError> {
+		value.set_context(&self.key);
+		value.validate_config()?;
+		Ok(value)
+	}
+
+	/// Reloads after a change and adjusts the watch targets to the new sources.
+	#[cfg(feature = "signal")]
+	async fn refresh(&self, watch_set: &mut WatchSet) {
+		loop {
+			let loaded = self.state().source.is_some();
+			match self.load().await {
+				Ok(_) => {}
+				// Expected while waiting for the file to appear
+				Err(LiveError::Load(FmtError::NotFound)) if !loaded => {}
+				Err(e) => {
+					if let Some(ref cb) = self.on_error {
+						cb(e);
+					}
+				}
+			}
+			// The base file may have moved, layer files may have appeared or
+			// disappeared
+			match watch_set.sync(self.watch_targets()) {
+				// A directory on the way to the missing file appeared, which may
+				// hold it already
+				Ok(true) if self.state().source.is_none() => {}
+				Ok(_) => break,
+				Err(e) => {
+					if let Some(ref cb) = self.on_error {
+						cb(LiveError::Signal(e));
+					}
+					break;
+				}
+			}
+		}
+	}
+
+	/// Returns the watch targets: the base file's directory plus every other
+	/// contributing file, and the directories optional files may appear in.
+	///
+	/// Before a successful load, the directory the base file is expected in is
+	/// watched, or the way to it if that does not exist yet.
+	#[cfg(feature = "signal")]
+	fn watch_targets(&self) -> Vec<Target> {
+		let state = self.state();
+		let Some((dir, name)) = self.base_dir(&state) else {
+			let expected = self.root().join(&self.key);
+			let target = match (
+				expected.parent(),
+				expected.file_name().and_then(|n| n.to_str()),
+			) {
+				(Some(dir), Some(name)) => match std::fs::canonicalize(dir) {
+					Ok(dir) => Some(base_target(&dir, &[name])),
+					Err(_) => pending_target(dir),
+				},
+				_ => None,
+			};
+			return target.into_iter().collect();
+		};
+		let optional = self.optional_files();
+		// One target per directory, covering the base and optional files in it
+		let mut dirs: Vec<(&Path, Vec<&str>)> = vec![(dir, vec![name])];
+		for (dir, name) in &optional {
+			match dirs.iter_mut().find(|(d, _)| d == dir) {
+				Some((_, names)) => names.push(name),
+				None => dirs.push((dir, vec![name])),
+			}
+		}
+		dirs
+			.iter()
+			.map(|(dir, names)| base_target(dir, names))
+			.chain(
+				state
+					.sources
+					.iter()
+					.filter(|path| {
+						!is_base_file(dir, name, path)
+							&& !optional
+								.iter()
+								.any(|(dir, name)| is_base_file(dir, name, path))
+					})
+					.cloned()
+					.map(Target::File),
+			)
+			.collect()
+	}
+
+	/// Returns true if an event for `path` may change the loaded value.
+	#[cfg(feature = "signal")]
+	fn is_watched(&self, path: &Path) -> bool {
+		let state = self.state();
+		match self.base_dir(&state) {
+			Some((dir, name)) => {
+				is_base_file(dir, name, path)
+					|| state.sources.iter().any(|s| s == path)
+					|| self
+						.optional_files()
+						.iter()
+						.any(|(dir, name)| is_base_file(dir, name, path))
+			}
+			// Only the fallback file is watched
+			None => true,
+		}
+	}
+
+	/// Returns the canonical directory and file name of every profile overlay
+	/// and file layer, whether it exists or not, so it is seen when it appears.
+	///
+	/// Files in directories that do not exist are left out.
+	#[cfg(feature = "signal")]
+	fn optional_files(&self) -> Vec<(PathBuf, String)> {
+		let root = self.root();
+		self
+			.layers
+			.optional_files(&self.key)
+			.into_iter()
+			.filter_map(|file| {
+				let expected = root.join(file);
+				let dir = std::fs::canonicalize(expected.parent()?).ok()?;
+				let name = expected.file_name()?.to_str()?.to_string();
+				Some((dir, name))
+			})
+			.collect()
+	}
+
+	/// Returns the directory of the base file and the name it is looked up by.
+	#[cfg(feature = "signal")]
+	fn base_dir<'a>(&'a self, state: &'a LoadState) -> Option<(&'a Path, &'a str)> {
+		let dir = state.source.as_deref()?.parent()?;
+		let name = Path::new(&self.key).file_name()?.to_str()?;
+		Some((dir, name))
+	}
+}
+
 impl<T> std::fmt::Debug for Live<T>
 where
 	T: std::fmt::Debug,
 {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let inner = &self.inner;
 		let mut s = f.debug_struct("Live");
-		s.field("store", &self.store);
-		s.field("loader", &self.loader);
-		s.field("key", &self.key);
+		s.field("store", &inner.store);
+		s.field("loader", &inner.loader);
+		s.field("key", &inner.key);
+		s.field("layers", &inner.layers);
 		#[cfg(feature = "signal")]
 		s.field("watching", &self.watch_state.is_some());
 		s.finish_non_exhaustive()
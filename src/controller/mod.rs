@@ -7,22 +7,61 @@
 //! - [`LiveDir`] - Directory-based controller
 
 #[cfg(feature = "signal")]
-use fsig::Watcher;
+use std::sync::{Arc, Mutex};
 #[cfg(feature = "signal")]
 use tokio::task::AbortHandle;
 
 #[cfg(feature = "signal")]
 pub(crate) struct WatchState {
-	pub watcher: Watcher,
 	pub abort_handle: AbortHandle,
+	pub status: Arc<Mutex<WatchStatus>>,
 }
 
+#[cfg(feature = "signal")]
+impl WatchState {
+	pub fn status(&self) -> WatchStatus {
+		self
+			.status
+			.lock()
+			.unwrap_or_else(|e| e.into_inner())
+			.clone()
+	}
+}
+
+mod apply;
+mod cache;
+mod diff;
 mod dir;
+mod document;
+mod edit;
+mod env;
 mod error;
+mod history;
+mod include;
+mod inherit;
+mod interpolate;
+mod layer;
 mod live;
+mod overrides;
 mod pattern;
+#[cfg(feature = "signal")]
+mod poll;
+mod reload;
+#[cfg(feature = "signal")]
+mod watch;
+mod write;
 
+pub use cache::LoadStatus;
+pub use diff::{ConfigDiff, FieldChange};
 pub use dir::{LiveDir, LiveDirBuilder};
+pub use env::{EnvCase, EnvOverrides};
 pub use error::LiveError;
+pub use history::Revision;
+pub use inherit::Inheritance;
+pub use layer::Layer;
 pub use live::{Live, LiveBuilder};
-pub use pattern::{KeyExtractorFn, KeyPattern, ScanMode, ScanResult};
+pub use overrides::Override;
+pub use pattern::{CommitMode, KeyExtractorFn, KeyPattern, Rejection, ScanMode, ScanResult};
+pub use reload::ReloadState;
+#[cfg(feature = "signal")]
+pub use watch::WatchStatus;
@@ -0,0 +1,287 @@
+/* src/controller/overrides.rs */
+
+//!
+//! Values pinned at runtime above whatever the files say.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use atomhold::{Entry, Meta, Store, UnloadPolicy};
+
+use super::apply::restore;
+
+/// A value pinned over the file value of a key.
+#[derive(Debug)]
+pub struct Override<T> {
+	pub key: String,
+	pub value: Arc<T>,
+	/// When the file value is restored, if the override was set with a TTL.
+	pub expires_at: Option<Instant>,
+}
+
+impl<T> Clone for Override<T> {
+	fn clone(&self) -> Self {
+		Self {
+			key: self.key.clone(),
+			value: Arc::clone(&self.value),
+			expires_at: self.expires_at,
+		}
+	}
+}
+
+struct Pinned<T> {
+	/// Distinguishes this override from later ones for the same key.
+	id: u64,
+	value: Arc<T>,
+	expires_at: Option<Instant>,
+	/// What the files say, published again when the override ends.
+	file: Option<Entry<T>>,
+}
+
+/// The active overrides of a controller.
+pub(crate) struct Overrides<T> {
+	pinned: Mutex<HashMap<String, Pinned<T>>>,
+	next_id: AtomicU64,
+}
+
+impl<T> Overrides<T>
+where
+	T: Clone + Send + Sync + 'static,
+{
+	pub fn new() -> Self {
+		Self {
+			pinned: Mutex::new(HashMap::new()),
+			next_id: AtomicU64::new(0),
+		}
+	}
+
+	fn pinned(&self) -> MutexGuard<'_, HashMap<String, Pinned<T>>> {
+		self.pinned.lock().unwrap_or_else(|e| e.into_inner())
+	}
+
+	/// Publishes `value` for `key` and keeps it there until the override is
+	/// cleared.
+	///
+	/// Returns the id checked when a TTL runs out.
+	pub fn set(&self, store: &Store<T>, key: &str, value: T, ttl: Option<Duration>) -> u64 {
+		let mut pinned = self.pinned();
+		let file = match pinned.remove(key) {
+			Some(previous) => previous.file,
+			None => store.get_entry(key),
+		};
+		// Keep the file's source so watching and reloads still find it
+		let (source, policy) = file
+			.as_ref()
+			.map(|entry| (entry.meta.source.clone(), entry.meta.policy))
+			.unwrap_or_default();
+		let value = store.insert(key.to_string(), value, source, policy);
+		let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+		pinned.insert(
+			key.to_string(),
+			Pinned {
+				id,
+				value,
+				expires_at: ttl.map(|ttl| Instant::now() + ttl),
+				file,
+			},
+		);
+		id
+	}
+
+	/// Like [`set`](Self::set), but restores the file value after `ttl`.
+	///
+	/// Must be called within a Tokio runtime.
+	pub fn set_for(self: &Arc<Self>, store: &Arc<Store<T>>, key: &str, value: T, ttl: Duration) {
+		let id = self.set(store, key, value, Some(ttl));
+		let overrides = Arc::downgrade(self);
+		let store = Arc::clone(store);
+		let key = key.to_string();
+		tokio::spawn(async move {
+			tokio::time::sleep(ttl).await;
+			if let Some(overrides) = overrides.upgrade() {
+				overrides.expire(&store, &key, id);
+			}
+		});
+	}
+
+	/// Ends the override of `key` and republishes the file value, or removes
+	/// the key if the files have none.
+	///
+	/// Returns false if `key` was not overridden.
+	pub fn clear(&self, store: &Store<T>, key: &str) -> bool {
+		let mut pinned = self.pinned();
+		match pinned.remove(key) {
+			Some(previous) => {
+				restore(store, key, previous.file);
+				true
+			}
+			None => false,
+		}
+	}
+
+	/// Ends every override, returning the cleared keys in order.
+	pub fn clear_all(&self, store: &Store<T>) -> Vec<String> {
+		let mut pinned = self.pinned();
+		let mut keys = Vec::with_capacity(pinned.len());
+		for (key, previous) in pinned.drain() {
+			restore(store, &key, previous.file);
+			keys.push(key);
+		}
+		keys.sort();
+		keys
+	}
+
+	/// Ends the override of `key` if it is still the one identified by `id`.
+	fn expire(&self, store: &Store<T>, key: &str, id: u64) {
+		let mut pinned = self.pinned();
+		if pinned.get(key).is_some_and(|p| p.id == id)
+			&& let Some(previous) = pinned.remove(key)
+		{
+			restore(store, key, previous.file);
+		}
+	}
+
+	/// Puts `previous` back in place of `published`, a file value that failed to
+	/// apply, unless something replaced it in the meantime (e.g. an override or
+	/// a rollback).
+	///
+	/// If `key` was overridden since, the value held back underneath is
+	/// restored instead.
+	pub fn restore(
+		&self,
+		store: &Store<T>,
+		key: &str,
+		published: &Arc<T>,
+		previous: Option<Entry<T>>,
+	) {
+		let mut pinned = self.pinned();
+		match pinned.get_mut(key) {
+			Some(pinned) => {
+				if pinned
+					.file
+					.as_ref()
+					.is_some_and(|file| Arc::ptr_eq(&file.value, published))
+				{
+					pinned.file = previous;
+				}
+			}
+			None => {
+				if store
+					.get(key)
+					.is_some_and(|current| Arc::ptr_eq(&current, published))
+				{
+					restore(store, key, previous);
+				}
+			}
+		}
+	}
+
+	/// Returns true if `key` is overridden.
+	pub fn contains(&self, key: &str) -> bool {
+		self.pinned().contains_key(key)
+	}
+
+	/// Returns what the files last said for `key`: the held back value if it is
+	/// overridden, the stored one otherwise.
+	pub fn file_entry(&self, store: &Store<T>, key: &str) -> Option<Entry<T>> {
+		match self.pinned().get(key) {
+			Some(pinned) => pinned.file.clone(),
+			None => store.get_entry(key),
+		}
+	}
+
+	/// Holds back a new file value of an overridden `key`.
+	///
+	/// Returns the value (and its source) if `key` is not overridden, so the
+	/// caller publishes it.
+	pub fn hold(
+		&self,
+		key: &str,
+		value: T,
+		source: PathBuf,
+		policy: UnloadPolicy,
+	) -> Option<(T, PathBuf)> {
+		let mut pinned = self.pinned();
+		let Some(pinned) = pinned.get_mut(key) else {
+			return Some((value, source));
+		};
+		let version = pinned.file.as_ref().map_or(0, |entry| entry.meta.version);
+		pinned.file = Some(Entry {
+			value: Arc::new(value),
+			meta: Meta {
+				source,
+				loaded_at: Instant::now(),
+				version,
+				policy,
+			},
+		});
+		None
+	}
+
+	/// Forgets the file value of an overridden `key` whose file was removed, so
+	/// clearing the override removes the key.
+	///
+	/// Returns false if `key` is not overridden.
+	pub fn forget(&self, key: &str) -> bool {
+		match self.pinned().get_mut(key) {
+			Some(pinned) => {
+				pinned.file = None;
+				true
+			}
+			None => false,
+		}
+	}
+
+	/// Returns every active override, ordered by key.
+	pub fn list(&self) -> Vec<Override<T>> {
+		let mut list: Vec<Override<T>> = self
+			.pinned()
+			.iter()
+			.map(|(key, pinned)| Override {
+				key: key.clone(),
+				value: Arc::clone(&pinned.value),
+				expires_at: pinned.expires_at,
+			})
+			.collect();
+		list.sort_by(|a, b| a.key.cmp(&b.key));
+		list
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_clear_restores_file_value() {
+		let store = Store::new();
+		store.insert(
+			"a".to_string(),
+			1,
+			PathBuf::from("a.json"),
+			UnloadPolicy::default(),
+		);
+		let overrides = Overrides::new();
+
+		overrides.set(&store, "a", 2, None);
+		assert_eq!(*store.get("a").unwrap(), 2);
+		assert!(
+			overrides
+				.hold("a", 3, PathBuf::from("a.json"), UnloadPolicy::default())
+				.is_none()
+		);
+		assert_eq!(*store.get("a").unwrap(), 2);
+
+		assert!(overrides.clear(&store, "a"));
+		assert_eq!(*store.get("a").unwrap(), 3);
+		assert!(!overrides.clear(&store, "a"));
+
+		// Without a file value the key goes away with the override
+		overrides.set(&store, "b", 1, None);
+		assert_eq!(overrides.clear_all(&store), vec!["b"]);
+		assert!(store.get("b").is_none());
+	}
+}
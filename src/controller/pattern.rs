@@ -5,25 +5,68 @@
 
 use std::sync::Arc;
 
+use super::diff::ConfigDiff;
+
 /// Result of a directory scan operation.
+///
+/// Every list is sorted by key.
 #[derive(Debug, Clone, Default)]
 pub struct ScanResult {
 	/// Keys that were newly added.
 	pub added: Vec<String>,
 	/// Keys that were updated (value changed).
 	pub updated: Vec<String>,
+	/// Keys that were reloaded without changes (store left untouched).
+	pub unchanged: Vec<String>,
 	/// Keys that failed to load (kept old value if available).
 	pub failed: Vec<(String, String)>,
 	/// Keys that were removed (file no longer exists).
 	pub removed: Vec<String>,
 	/// Keys retained due to Persistent policy.
 	pub retained: Vec<String>,
+	/// Keys whose new value `on_apply` rejected, with the reason (old value restored).
+	pub rolled_back: Vec<(String, String)>,
+	/// Keys served from the last-known-good cache by `load()`, with the reason
+	/// their source could not be used.
+	pub cached: Vec<(String, String)>,
+	/// Keys whose new file value was loaded and validated but held back by a
+	/// runtime override.
+	pub overridden: Vec<String>,
+	/// Field-level changes of every updated key.
+	pub diffs: Vec<(String, ConfigDiff)>,
 }
 
 impl ScanResult {
-	/// Returns an iterator over all successfully loaded keys (both added and updated).
+	/// Returns an iterator over all successfully loaded keys (added, updated and unchanged).
 	pub fn loaded(&self) -> impl Iterator<Item = &String> {
-		self.added.iter().chain(self.updated.iter())
+		self
+			.added
+			.iter()
+			.chain(self.updated.iter())
+			.chain(self.unchanged.iter())
+	}
+
+	/// Returns the field-level changes of an updated key.
+	pub fn diff(&self, key: &str) -> Option<&ConfigDiff> {
+		self
+			.diffs
+			.iter()
+			.find(|(k, _)| k == key)
+			.map(|(_, diff)| diff)
+	}
+
+	/// Sorts every list by key.
+	pub(crate) fn sort(&mut self) {
+		self.added.sort();
+		self.updated.sort();
+		self.unchanged.sort();
+		self.failed.sort_by(|a, b| a.0.cmp(&b.0));
+		self.removed.sort();
+		self.retained.sort();
+		self.rolled_back.sort_by(|a, b| a.0.cmp(&b.0));
+		self.cached.sort_by(|a, b| a.0.cmp(&b.0));
+		self.overridden.sort();
+		self.diffs.sort_by(|a, b| a.0.cmp(&b.0));
 	}
 }
 
@@ -153,6 +196,32 @@ impl KeyPattern {
 	}
 }
 
+/// Defines how a `LiveDir` scan writes its changes to the store.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CommitMode {
+	/// Applies every entry that loaded successfully, one store write at a time.
+	/// Failed entries keep their old value.
+	#[default]
+	Partial,
+	/// Publishes all changes of a scan with a single store replacement, or none
+	/// of them if any entry fails.
+	///
+	/// Readers never observe a half-applied directory. Rejected changes are
+	/// reported in `ScanResult::failed`. The whole store is republished, so
+	/// subscribers see an update for every entry and concurrent writes by others
+	/// may be lost; use a store dedicated to this `LiveDir`.
+	Atomic,
+}
+
+/// Outcome of a failed `LiveDir` `validate_all` check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rejection {
+	/// Rejects individual keys, each with a reason.
+	Keys(Vec<(String, String)>),
+	/// Rejects every change of the scan.
+	All(String),
+}
+
 /// Defines what to scan within the directory.
 #[derive(Debug, Clone, Default)]
 pub enum ScanMode {
@@ -0,0 +1,313 @@
+/* src/controller/poll.rs */
+
+//!
+//! Change detection by polling, for filesystems without usable events.
+
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use fsig::Target;
+use tokio::task::JoinHandle;
+
+/// How long after a poll a file may still change without changing its
+/// modification time, for filesystems with coarse timestamps (FAT: 2s).
+const RACY: Duration = Duration::from_secs(2);
+
+/// What a file looked like when it was last polled.
+#[derive(Debug, PartialEq)]
+struct Stamp {
+	modified: Option<SystemTime>,
+	len: u64,
+	/// Content hash of files modified too recently for their modification
+	/// time to tell later changes apart.
+	hash: Option<u64>,
+}
+
+/// The files of a target at one point in time.
+#[derive(Debug)]
+struct Snapshot {
+	taken: SystemTime,
+	stamps: HashMap<PathBuf, Stamp>,
+}
+
+impl Default for Snapshot {
+	fn default() -> Self {
+		Self {
+			taken: SystemTime::UNIX_EPOCH,
+			stamps: HashMap::new(),
+		}
+	}
+}
+
+/// The files a target covers.
+struct Scope {
+	target: Target,
+	ignore_hidden: bool,
+}
+
+/// Polls the files of a watch target and reports the ones that changed.
+///
+/// Directories are walked recursively, except for filtered targets, whose
+/// files are expected at the top level and must match the target's patterns.
+/// Files are read on the blocking thread pool.
+pub(crate) struct Poller {
+	scope: Arc<Scope>,
+	snapshot: Snapshot,
+	/// The first snapshot, taken in the background.
+	initial: Option<JoinHandle<Snapshot>>,
+}
+
+impl Poller {
+	/// Starts polling `target`, taking the snapshot later polls compare to.
+	///
+	/// Must be called within a Tokio runtime.
+	pub fn new(target: Target, ignore_hidden: bool) -> Self {
+		let scope = Arc::new(Scope {
+			target,
+			ignore_hidden,
+		});
+		let initial = {
+			let scope = Arc::clone(&scope);
+			tokio::task::spawn_blocking(move || scope.take(&Snapshot::default()))
+		};
+		Self {
+			scope,
+			snapshot: Snapshot::default(),
+			initial: Some(initial),
+		}
+	}
+
+	/// Returns the paths added, changed or removed since the last poll.
+	pub async fn poll(&mut self) -> Vec<PathBuf> {
+		if let Some(initial) = self.initial.take() {
+			self.snapshot = initial.await.unwrap_or_default();
+		}
+		let scope = Arc::clone(&self.scope);
+		let previous = std::mem::take(&mut self.snapshot);
+		let Ok((snapshot, changed)) = tokio::task::spawn_blocking(move || {
+			let snapshot = scope.take(&previous);
+			let changed = changed(&previous, &snapshot);
+			(snapshot, changed)
+		})
+		.await
+		else {
+			return Vec::new();
+		};
+		self.snapshot = snapshot;
+		changed
+	}
+}
+
+impl Scope {
+	fn take(&self, previous: &Snapshot) -> Snapshot {
+		let mut snapshot = Snapshot {
+			taken: SystemTime::now(),
+			stamps: HashMap::new(),
+		};
+		// The first snapshot hashes what a change right after it could miss
+		let since = match previous.taken {
+			SystemTime::UNIX_EPOCH => snapshot.taken,
+			taken => taken,
+		};
+		match &self.target {
+			Target::File(path) => {
+				if let Some(stamp) = stamp(path, since) {
+					snapshot.stamps.insert(path.clone(), stamp);
+				}
+			}
+			Target::Directory(path) => self.walk(path, true, since, &mut snapshot.stamps),
+			Target::Filtered { path, .. } => self.walk(path, false, since, &mut snapshot.stamps),
+		}
+		snapshot
+	}
+
+	fn walk(
+		&self,
+		dir: &Path,
+		recursive: bool,
+		since: SystemTime,
+		stamps: &mut HashMap<PathBuf, Stamp>,
+	) {
+		let Ok(entries) = fs::read_dir(dir) else {
+			return;
+		};
+		for entry in entries.flatten() {
+			let name = entry.file_name();
+			let Some(name) = name.to_str() else {
+				continue;
+			};
+			if self.ignore_hidden && name.starts_with('.') {
+				continue;
+			}
+			let path = entry.path();
+			// Symlinked directories are not followed, so links cannot loop
+			if entry.file_type().is_ok_and(|t| t.is_dir()) {
+				if recursive {
+					self.walk(&path, true, since, stamps);
+				}
+				continue;
+			}
+			if !self.includes(name) {
+				continue;
+			}
+			if let Some(stamp) = stamp(&path, since) {
+				stamps.insert(path, stamp);
+			}
+		}
+	}
+
+	/// Returns true if a file named `name` matches the patterns of a filtered
+	/// target.
+	fn includes(&self, name: &str) -> bool {
+		let Target::Filtered {
+			include, exclude, ..
+		} = &self.target
+		else {
+			return true;
+		};
+		!exclude.iter().any(|pattern| glob(pattern, name))
+			&& (include.is_empty() || include.iter().any(|pattern| glob(pattern, name)))
+	}
+}
+
+/// Returns the paths whose stamp differs between the two snapshots.
+fn changed(previous: &Snapshot, next: &Snapshot) -> Vec<PathBuf> {
+	let differs = |old: &Stamp, new: &Stamp| {
+		old.modified != new.modified
+			|| old.len != new.len
+			|| matches!((old.hash, new.hash), (Some(a), Some(b)) if a != b)
+	};
+	let mut changed: Vec<PathBuf> = next
+		.stamps
+		.iter()
+		.filter(|(path, stamp)| {
+			previous
+				.stamps
+				.get(*path)
+				.is_none_or(|old| differs(old, stamp))
+		})
+		.map(|(path, _)| path.clone())
+		.chain(
+			previous
+				.stamps
+				.keys()
+				.filter(|path| !next.stamps.contains_key(*path))
+				.cloned(),
+		)
+		.collect();
+	changed.sort();
+	changed
+}
+
+/// Returns the stamp of `path`, following symlinks, or `None` if it is gone.
+///
+/// The content is only hashed if the file was modified within [`RACY`] of
+/// `since`, as an older modification time changes with any later write.
+fn stamp(path: &Path, since: SystemTime) -> Option<Stamp> {
+	let metadata = fs::metadata(path).ok()?;
+	let modified = metadata.modified().ok();
+	let racy = modified.is_none_or(|modified| modified + RACY >= since);
+	let hash = (metadata.is_file() && racy).then(|| {
+		let mut hasher = DefaultHasher::new();
+		fs::read(path).ok().hash(&mut hasher);
+		hasher.finish()
+	});
+	Some(Stamp {
+		modified,
+		len: metadata.len(),
+		hash,
+	})
+}
+
+/// Matches a file name against a glob pattern with `*`, `?` and `[...]`.
+fn glob(pattern: &str, name: &str) -> bool {
+	let pattern: Vec<char> = pattern.chars().collect();
+	let name: Vec<char> = name.chars().collect();
+	glob_chars(&pattern, &name)
+}
+
+fn glob_chars(pattern: &[char], name: &[char]) -> bool {
+	match pattern.split_first() {
+		None => name.is_empty(),
+		Some(('*', rest)) => (0..=name.len()).any(|skip| glob_chars(rest, &name[skip..])),
+		Some(('?', rest)) => !name.is_empty() && glob_chars(rest, &name[1..]),
+		Some(('[', rest)) => {
+			let Some(end) = rest.iter().skip(1).position(|&c| c == ']').map(|i| i + 1) else {
+				return name.first() == Some(&'[') && glob_chars(rest, &name[1..]);
+			};
+			let Some((&c, tail)) = name.split_first() else {
+				return false;
+			};
+			let (negated, class) = match rest[0] {
+				'!' | '^' => (true, &rest[1..end]),
+				_ => (false, &rest[..end]),
+			};
+			let mut found = false;
+			let mut i = 0;
+			while i < class.len() {
+				if i + 2 < class.len() && class[i + 1] == '-' {
+					found |= (class[i]..=class[i + 2]).contains(&c);
+					i += 3;
+				} else {
+					found |= class[i] == c;
+					i += 1;
+				}
+			}
+			found != negated && glob_chars(&rest[end + 1..], tail)
+		}
+		Some((&c, rest)) => name.first() == Some(&c) && glob_chars(rest, &name[1..]),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn test_poll_reports_changed_files() {
+		let dir = tempfile::tempdir().unwrap();
+		let root = dir.path().to_path_buf();
+		fs::write(root.join("a.json"), "1").unwrap();
+		fs::write(root.join(".hidden"), "1").unwrap();
+		let mut poller = Poller::new(Target::Directory(root.clone()), true);
+		assert!(poller.poll().await.is_empty());
+
+		// Same size, and possibly the same mtime
+		fs::write(root.join("a.json"), "2").unwrap();
+		fs::write(root.join(".hidden"), "2").unwrap();
+		fs::create_dir(root.join("sub")).unwrap();
+		fs::write(root.join("sub").join("b.json"), "1").unwrap();
+		assert_eq!(
+			poller.poll().await,
+			vec![root.join("a.json"), root.join("sub").join("b.json")]
+		);
+
+		fs::remove_file(root.join("a.json")).unwrap();
+		assert_eq!(poller.poll().await, vec![root.join("a.json")]);
+	}
+
+	#[tokio::test]
+	async fn test_poll_filtered_target() {
+		let dir = tempfile::tempdir().unwrap();
+		let root = dir.path().to_path_buf();
+		let target = Target::Filtered {
+			path: root.clone(),
+			include: vec!["app".to_string(), "app.*".to_string()],
+			exclude: Vec::new(),
+		};
+		let mut poller = Poller::new(target, false);
+		assert!(poller.poll().await.is_empty());
+		fs::write(root.join("app.toml"), "1").unwrap();
+		fs::write(root.join("other.json"), "1").unwrap();
+		fs::write(root.join("apple.json"), "1").unwrap();
+		assert_eq!(poller.poll().await, vec![root.join("app.toml")]);
+
+		assert!(glob("[[]a[*]", "[a*"));
+		assert!(glob("a?[0-9]", "ab7"));
+		assert!(!glob("a[!b]", "ab"));
+	}
+}
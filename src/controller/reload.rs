@@ -0,0 +1,147 @@
+/* src/controller/reload.rs */
+
+//!
+//! Serialized reloads, coalescing the ones requested while another runs.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use tokio::sync::Mutex;
+
+use super::LiveError;
+
+/// Whether a controller is reloading.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReloadState {
+	/// A reload is running.
+	pub in_progress: bool,
+	/// Reloads waiting for the running one. Full reloads among them are served
+	/// by a single follow-up.
+	pub pending: usize,
+}
+
+/// The last reload, kept for coalescing.
+struct Last<R> {
+	/// Number of the run, counted from 1.
+	run: u64,
+	/// Its result, if it succeeded and covered everything.
+	result: Option<R>,
+}
+
+/// Runs the reloads of a controller one at a time.
+pub(crate) struct Reloads<R> {
+	last: Mutex<Last<R>>,
+	started: AtomicU64,
+	running: AtomicUsize,
+	waiting: AtomicUsize,
+}
+
+impl<R: Clone> Reloads<R> {
+	pub fn new() -> Self {
+		Self {
+			last: Mutex::new(Last {
+				run: 0,
+				result: None,
+			}),
+			started: AtomicU64::new(0),
+			running: AtomicUsize::new(0),
+			waiting: AtomicUsize::new(0),
+		}
+	}
+
+	/// Runs `reload` once no other reload is running.
+	///
+	/// A `full` reload is skipped if another full reload started after this
+	/// call and succeeded in the meantime, as that one saw every change this
+	/// call was made for. Its result is returned instead, without `E`.
+	pub async fn run<E, F>(&self, full: bool, reload: F) -> Result<(R, E), LiveError>
+	where
+		E: Default,
+		F: Future<Output = Result<(R, E), LiveError>>,
+	{
+		let ticket = self.started.load(Ordering::SeqCst);
+		let mut last = {
+			let _waiting = Counted::new(&self.waiting);
+			self.last.lock().await
+		};
+		if full
+			&& last.run > ticket
+			&& let Some(result) = &last.result
+		{
+			return Ok((result.clone(), E::default()));
+		}
+		let run = self.started.fetch_add(1, Ordering::SeqCst) + 1;
+		let result = {
+			let _running = Counted::new(&self.running);
+			reload.await
+		};
+		*last = Last {
+			run,
+			result: match &result {
+				Ok((shared, _)) if full => Some(shared.clone()),
+				_ => None,
+			},
+		};
+		result
+	}
+
+	pub fn state(&self) -> ReloadState {
+		ReloadState {
+			in_progress: self.running.load(Ordering::SeqCst) > 0,
+			pending: self.waiting.load(Ordering::SeqCst),
+		}
+	}
+}
+
+/// Counts itself in `count` while alive, so cancelled calls are not counted.
+struct Counted<'a>(&'a AtomicUsize);
+
+impl<'a> Counted<'a> {
+	fn new(count: &'a AtomicUsize) -> Self {
+		count.fetch_add(1, Ordering::SeqCst);
+		Self(count)
+	}
+}
+
+impl Drop for Counted<'_> {
+	fn drop(&mut self) {
+		self.0.fetch_sub(1, Ordering::SeqCst);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::Arc;
+	use std::time::Duration;
+
+	#[tokio::test]
+	async fn test_waiting_full_reloads_share_one_follow_up() {
+		let reloads = Arc::new(Reloads::<u64>::new());
+		let runs = Arc::new(AtomicU64::new(0));
+		let reload = |reloads: Arc<Reloads<u64>>, runs: Arc<AtomicU64>| async move {
+			reloads
+				.run(true, async {
+					tokio::time::sleep(Duration::from_millis(50)).await;
+					Ok::<_, LiveError>((runs.fetch_add(1, Ordering::SeqCst) + 1, ()))
+				})
+				.await
+		};
+
+		let first = tokio::spawn(reload(reloads.clone(), runs.clone()));
+		tokio::time::sleep(Duration::from_millis(10)).await;
+		assert!(reloads.state().in_progress);
+		let waiting: Vec<_> = (0..5)
+			.map(|_| tokio::spawn(reload(reloads.clone(), runs.clone())))
+			.collect();
+		tokio::time::sleep(Duration::from_millis(10)).await;
+		assert_eq!(reloads.state().pending, 5);
+
+		assert_eq!(first.await.unwrap().unwrap().0, 1);
+		for handle in waiting {
+			assert_eq!(handle.await.unwrap().unwrap().0, 2);
+		}
+		assert_eq!(runs.load(Ordering::SeqCst), 2);
+		assert_eq!(reloads.state(), ReloadState::default());
+	}
+}
@@ -0,0 +1,451 @@
+/* src/controller/watch.rs */
+
+//!
+//! A dynamic set of filesystem watchers feeding a single event stream.
+
+use std::future::{Future, poll_fn};
+use std::hash::{BuildHasher, RandomState};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::task::Poll;
+use std::time::Duration;
+
+use fsig::{Config as WatcherConfig, Event, Target, Watcher};
+use tokio::sync::broadcast::{
+	Receiver,
+	error::{RecvError, TryRecvError},
+};
+use tokio::time::Instant;
+
+use super::LiveError;
+use super::poll::Poller;
+
+/// How often watched paths are checked to still exist.
+const HEALTH_INTERVAL: Duration = Duration::from_secs(1);
+/// Delay before the first attempt to re-create a failed watcher, doubled on
+/// every further attempt.
+const BACKOFF_MIN: Duration = Duration::from_millis(100);
+const BACKOFF_MAX: Duration = Duration::from_secs(10);
+/// Poll interval of targets that fell back to polling, unless one is set.
+const POLL_FALLBACK: Duration = Duration::from_secs(2);
+
+/// Health of a controller's filesystem watching.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchStatus {
+	/// Changes are picked up as they happen.
+	Active,
+	/// Events were missed, so a full reload is catching up.
+	Resyncing,
+	/// A watcher could not be created or stopped working. Changes are missed
+	/// until it is re-created, which is retried with a growing delay.
+	Retrying { attempt: u32, reason: String },
+	/// Changes are picked up by polling, either as configured or because a
+	/// watcher could not be created.
+	Polling,
+}
+
+/// What the watch loop of a controller has to act on.
+pub(crate) enum Change {
+	/// These files changed.
+	Events(Vec<PathBuf>),
+	/// Events were dropped, so anything may have changed.
+	Lagged(u64),
+	/// A watcher stopped working.
+	Failed(String),
+	/// Every watcher works again after `attempts` tries; changes in between
+	/// were missed.
+	Restored(u32),
+	/// A periodic rescan is due.
+	Rescan,
+	/// A target is polled because its watcher could not be created.
+	Fallback(String),
+}
+
+impl Change {
+	/// Returns the transition to report through `on_error`, if any.
+	pub fn error(&self) -> Option<LiveError> {
+		match self {
+			Change::Events(_) | Change::Rescan => None,
+			Change::Lagged(n) => Some(LiveError::Lagged(*n)),
+			Change::Failed(reason) => Some(LiveError::WatcherStopped(reason.clone())),
+			Change::Restored(attempts) => Some(LiveError::WatcherRestored(*attempts)),
+			Change::Fallback(reason) => Some(LiveError::PollingFallback(reason.clone())),
+		}
+	}
+}
+
+/// Periodic full reloads alongside the watch events.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Rescan {
+	interval: Duration,
+	jitter: Duration,
+}
+
+impl Rescan {
+	pub fn new(interval: Duration, jitter: Duration) -> Self {
+		Self { interval, jitter }
+	}
+
+	/// Returns when the rescan after one at `now` is due.
+	fn after(&self, now: Instant) -> Instant {
+		let jitter = match self.jitter.as_nanos() as u64 {
+			0 => 0,
+			max => RandomState::new().hash_one(now) % (max + 1),
+		};
+		now + self.interval + Duration::from_nanos(jitter)
+	}
+}
+
+struct Retry {
+	attempt: u32,
+	at: Instant,
+}
+
+struct Watched {
+	target: Target,
+	source: Source,
+}
+
+enum Source {
+	Events {
+		// Kept alive for as long as the target is watched.
+		_watcher: Watcher,
+		rx: Receiver<Event>,
+	},
+	Polled(Poller),
+}
+
+/// Watches a changing set of targets and yields events from all of them.
+///
+/// Owned by the background task of a controller so the set can be adjusted
+/// after each reload (e.g. when a layer file appears). Watchers that fail are
+/// re-created with backoff; targets whose watcher cannot be created at all
+/// (e.g. at the inotify watch limit) are polled instead.
+pub(crate) struct WatchSet {
+	config: WatcherConfig,
+	watched: Vec<Watched>,
+	/// Everything that should be watched, including failed targets.
+	targets: Vec<Target>,
+	retry: Option<Retry>,
+	rescan: Option<(Rescan, Instant)>,
+	/// Polls every target at this interval instead of watching it.
+	poll: Option<Duration>,
+	next_poll: Option<Instant>,
+	/// Why a target fell back to polling, until that is reported.
+	fallback: Option<String>,
+	status: Arc<Mutex<WatchStatus>>,
+}
+
+impl WatchSet {
+	pub fn new(config: WatcherConfig, rescan: Option<Rescan>, poll: Option<Duration>) -> Self {
+		Self {
+			config,
+			watched: Vec::new(),
+			targets: Vec::new(),
+			retry: None,
+			rescan: rescan.map(|rescan| (rescan, rescan.after(Instant::now()))),
+			poll,
+			next_poll: None,
+			fallback: None,
+			status: Arc::new(Mutex::new(WatchStatus::Active)),
+		}
+	}
+
+	/// Returns the status, shared with the controller for querying.
+	pub fn status(&self) -> Arc<Mutex<WatchStatus>> {
+		Arc::clone(&self.status)
+	}
+
+	fn set_status(&self, status: WatchStatus) {
+		*self.status.lock().unwrap_or_else(|e| e.into_inner()) = status;
+	}
+
+	/// Watches exactly `targets`, creating new watchers and dropping stale ones.
+	///
+	/// Returns true if the set changed. Targets that fail are retried later.
+	pub fn sync<I>(&mut self, targets: I) -> Result<bool, fsig::Error>
+	where
+		I: IntoIterator<Item = Target>,
+	{
+		self.targets = targets.into_iter().collect();
+		let before = self.watched.len();
+		self.watched.retain(|w| self.targets.contains(&w.target));
+		let mut changed = self.watched.len() != before;
+		let mut failed = None;
+
+		for target in &self.targets {
+			if self.watched.iter().any(|w| w.target == *target) {
+				continue;
+			}
+			changed = true;
+			if self.poll.is_some() {
+				self.watched.push(self.polled(target));
+				continue;
+			}
+			match Watcher::new(target.clone(), self.config.clone()) {
+				Ok(watcher) => {
+					let rx = watcher.subscribe();
+					self.watched.push(Watched {
+						target: target.clone(),
+						source: Source::Events {
+							_watcher: watcher,
+							rx,
+						},
+					});
+				}
+				// The watcher itself is unavailable, not the path
+				Err(e @ (fsig::Error::Notify(_) | fsig::Error::Io(_))) => {
+					self.fallback.get_or_insert_with(|| e.to_string());
+					self.watched.push(self.polled(target));
+				}
+				Err(e) => failed = failed.or(Some(e)),
+			}
+		}
+		match failed {
+			Some(e) => {
+				self.schedule_retry(e.to_string());
+				Err(e)
+			}
+			None => Ok(changed),
+		}
+	}
+
+	fn polled(&self, target: &Target) -> Watched {
+		Watched {
+			target: target.clone(),
+			source: Source::Polled(Poller::new(target.clone(), self.config.ignore_hidden)),
+		}
+	}
+
+	/// Waits for the next change, re-creating failed watchers and polling in
+	/// the meantime.
+	///
+	/// Periodic rescans are due as configured, whether or not events arrive.
+	pub async fn next(&mut self) -> Change {
+		loop {
+			if let Some(reason) = self.fallback.take() {
+				return Change::Fallback(reason);
+			}
+			let polling = self
+				.watched
+				.iter()
+				.any(|w| matches!(w.source, Source::Polled(_)));
+			if self.retry.is_none() {
+				self.set_status(match polling {
+					true => WatchStatus::Polling,
+					false => WatchStatus::Active,
+				});
+			}
+			let mut deadline = match &self.retry {
+				Some(retry) => retry.at,
+				None => Instant::now() + HEALTH_INTERVAL,
+			};
+			if let Some((_, at)) = &self.rescan {
+				deadline = deadline.min(*at);
+			}
+			match (polling, self.next_poll) {
+				(true, Some(at)) => deadline = deadline.min(at),
+				(true, None) => {
+					let at = Instant::now() + self.poll.unwrap_or(POLL_FALLBACK);
+					self.next_poll = Some(at);
+					deadline = deadline.min(at);
+				}
+				(false, _) => self.next_poll = None,
+			}
+			match tokio::time::timeout_at(deadline, self.recv_from()).await {
+				Ok((_, Ok(event))) => return Change::Events(event.paths),
+				Ok((_, Err(RecvError::Lagged(n)))) => {
+					self.set_status(WatchStatus::Resyncing);
+					return Change::Lagged(n);
+				}
+				Ok((index, Err(RecvError::Closed))) => {
+					let watched = self.watched.remove(index);
+					if let Some(change) = self.fail(format!("{:?} closed", watched.target)) {
+						return change;
+					}
+				}
+				Err(_) => {
+					if let Some((rescan, at)) = &mut self.rescan
+						&& *at <= Instant::now()
+					{
+						*at = rescan.after(Instant::now());
+						return Change::Rescan;
+					}
+					if self.next_poll.is_some_and(|at| at <= Instant::now()) {
+						self.next_poll = None;
+						let paths = self.poll_all().await;
+						if !paths.is_empty() {
+							return Change::Events(paths);
+						}
+					}
+					if let Some(attempt) = self.retry.as_ref().map(|retry| retry.attempt) {
+						// Failing again schedules the next attempt
+						if self.sync(self.targets.clone()).is_ok() {
+							self.retry = None;
+							self.set_status(WatchStatus::Resyncing);
+							return Change::Restored(attempt);
+						}
+						continue;
+					}
+					if let Some(change) = self.check_roots() {
+						return change;
+					}
+				}
+			}
+		}
+	}
+
+	/// Returns the paths that changed in any polled target.
+	async fn poll_all(&mut self) -> Vec<PathBuf> {
+		let mut paths = Vec::new();
+		for watched in &mut self.watched {
+			if let Source::Polled(poller) = &mut watched.source {
+				paths.extend(poller.poll().await);
+			}
+		}
+		paths
+	}
+
+	/// Drops watchers whose watched path was removed, which silently ends them.
+	///
+	/// Polled targets notice removal on their own.
+	fn check_roots(&mut self) -> Option<Change> {
+		let index = self.watched.iter().position(|w| {
+			matches!(w.source, Source::Events { .. }) && !watched_path(&w.target).exists()
+		})?;
+		let watched = self.watched.remove(index);
+		let reason = format!("{} was removed", watched_path(&watched.target).display());
+		self.fail(reason)
+	}
+
+	/// Schedules re-creating the missing watchers.
+	///
+	/// Returns the change to report unless a retry was pending already.
+	fn fail(&mut self, reason: String) -> Option<Change> {
+		let first = self.retry.is_none();
+		self.schedule_retry(reason.clone());
+		first.then_some(Change::Failed(reason))
+	}
+
+	fn schedule_retry(&mut self, reason: String) {
+		let attempt = self.retry.as_ref().map_or(1, |retry| retry.attempt + 1);
+		let delay = BACKOFF_MIN
+			.saturating_mul(1 << (attempt - 1).min(16))
+			.min(BACKOFF_MAX);
+		self.retry = Some(Retry {
+			attempt,
+			at: Instant::now() + delay,
+		});
+		self.set_status(WatchStatus::Retrying { attempt, reason });
+	}
+
+	/// Receives the next event from any watched target, along with the index
+	/// of that target.
+	///
+	/// Pends forever while nothing is watched.
+	async fn recv_from(&mut self) -> (usize, Result<Event, RecvError>) {
+		let mut pending: Vec<_> = self
+			.watched
+			.iter_mut()
+			.enumerate()
+			.filter_map(|(index, w)| match &mut w.source {
+				Source::Events { rx, .. } => Some((index, Box::pin(rx.recv()))),
+				Source::Polled(_) => None,
+			})
+			.collect();
+
+		poll_fn(|cx| {
+			for (index, fut) in pending.iter_mut() {
+				if let Poll::Ready(result) = fut.as_mut().poll(cx) {
+					return Poll::Ready((*index, result));
+				}
+			}
+			Poll::Pending
+		})
+		.await
+	}
+
+	/// Returns an already queued event from any watched target, if there is one.
+	pub fn try_recv(&mut self) -> Result<Option<Event>, RecvError> {
+		for watched in &mut self.watched {
+			let Source::Events { rx, .. } = &mut watched.source else {
+				continue;
+			};
+			match rx.try_recv() {
+				Ok(event) => return Ok(Some(event)),
+				Err(TryRecvError::Lagged(n)) => return Err(RecvError::Lagged(n)),
+				Err(TryRecvError::Empty | TryRecvError::Closed) => {}
+			}
+		}
+		Ok(None)
+	}
+}
+
+/// Returns the path a watcher for `target` is registered on.
+fn watched_path(target: &Target) -> &Path {
+	match target {
+		Target::File(path) => match path.parent() {
+			Some(parent) if !parent.as_os_str().is_empty() => parent,
+			_ => path,
+		},
+		Target::Directory(path) | Target::Filtered { path, .. } => path,
+	}
+}
+
+/// Watches `dir` for files named any of `names` with any (or no) extension, so
+/// a file that is replaced, recreated or switched to another format is still seen.
+pub(crate) fn base_target(dir: &Path, names: &[&str]) -> Target {
+	Target::Filtered {
+		path: dir.to_path_buf(),
+		include: names
+			.iter()
+			.map(|name| escape(name))
+			.flat_map(|name| [format!("{}.*", name), name])
+			.collect(),
+		exclude: Vec::new(),
+	}
+}
+
+/// Returns true if `path` is a file in `dir` that [`base_target`] is meant for.
+///
+/// The directory watch is recursive and only filtered with the `match` feature,
+/// so events are checked again.
+pub(crate) fn is_base_file(dir: &Path, name: &str, path: &Path) -> bool {
+	path.parent() == Some(dir)
+		&& (path.file_name().is_some_and(|n| n == name)
+			|| path.file_stem().is_some_and(|stem| stem == name))
+}
+
+/// Watches for the first missing component of `path` to appear in its nearest
+/// existing ancestor.
+///
+/// Returns `None` if `path` cannot be resolved that way (e.g. it ends in `..`).
+pub(crate) fn pending_target(path: &Path) -> Option<Target> {
+	let mut missing = path;
+	while let Some(parent) = missing.parent() {
+		let dir = match parent.as_os_str().is_empty() {
+			true => Path::new("."),
+			false => parent,
+		};
+		if let Ok(dir) = std::fs::canonicalize(dir) {
+			return Some(Target::File(dir.join(missing.file_name()?)));
+		}
+		missing = parent;
+	}
+	None
+}
+
+/// Escapes glob metacharacters in `name`.
+fn escape(name: &str) -> String {
+	let mut escaped = String::with_capacity(name.len());
+	for c in name.chars() {
+		if matches!(c, '*' | '?' | '[' | ']' | '{' | '}' | '\\') {
+			escaped.push('[');
+			escaped.push(c);
+			escaped.push(']');
+		} else {
+			escaped.push(c);
+		}
+	}
+	escaped
+}
@@ -0,0 +1,199 @@
+/* src/controller/write.rs */
+
+//!
+//! Writing values back to their source files.
+
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use fmtstruct::FmtError;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::fs;
+
+use super::LiveError;
+
+/// Serializes `value` in the format matching the extension of `path`.
+pub(crate) fn serialize<T: Serialize>(value: &T, path: &Path) -> Result<Vec<u8>, LiveError> {
+	let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+	let failed = |e: &dyn std::fmt::Display| LiveError::Write(format!("{}: {}", path.display(), e));
+	match extension {
+		"json" => serde_json::to_vec_pretty(value)
+			.map(|mut bytes| {
+				bytes.push(b'\n');
+				bytes
+			})
+			.map_err(|e| failed(&e)),
+		#[cfg(feature = "toml")]
+		"toml" => toml::to_string_pretty(value)
+			.map(String::into_bytes)
+			.map_err(|e| failed(&e)),
+		#[cfg(feature = "yaml")]
+		"yaml" | "yml" => serde_yaml::to_string(value)
+			.map(String::into_bytes)
+			.map_err(|e| failed(&e)),
+		_ => Err(failed(&"format cannot be written")),
+	}
+}
+
+/// Parses a document in the format matching the extension of `path`.
+pub(crate) fn deserialize(bytes: &[u8], path: &Path) -> Result<Value, LiveError> {
+	let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+	let failed = |e: &dyn std::fmt::Display| {
+		LiveError::Load(FmtError::ParseError(format!("{}: {}", path.display(), e)))
+	};
+	match extension {
+		"json" => serde_json::from_slice(bytes).map_err(|e| failed(&e)),
+		#[cfg(feature = "toml")]
+		"toml" => std::str::from_utf8(bytes)
+			.map_err(|e| failed(&e))
+			.and_then(|text| toml::from_str(text).map_err(|e| failed(&e))),
+		#[cfg(feature = "yaml")]
+		"yaml" | "yml" => serde_yaml::from_slice(bytes).map_err(|e| failed(&e)),
+		_ => Err(failed(&"format cannot be read")),
+	}
+}
+
+/// Extensions of the formats [`serialize`] and [`deserialize`] support.
+pub(crate) const EXTENSIONS: &[&str] = &[
+	"json",
+	#[cfg(feature = "toml")]
+	"toml",
+	#[cfg(feature = "yaml")]
+	"yaml",
+	#[cfg(feature = "yaml")]
+	"yml",
+];
+
+/// Checks that `key` names a single file or directory.
+pub(crate) fn check_key(key: &str) -> Result<(), LiveError> {
+	if key.is_empty() || key.starts_with('.') || key.contains(['/', '\\']) {
+		return Err(LiveError::Write(format!("invalid key: {:?}", key)));
+	}
+	Ok(())
+}
+
+/// Fails unless `path` (which may not exist yet) lies within `root`.
+///
+/// Symlinks are resolved, so a linked directory cannot lead outside the root.
+pub(crate) async fn check_sandbox(root: &Path, path: &Path) -> Result<(), LiveError> {
+	let root = fs::canonicalize(root).await?;
+	// The nearest existing ancestor decides, as the rest is created below it
+	let mut dir = path.parent();
+	while let Some(current) = dir {
+		match fs::canonicalize(current).await {
+			Ok(resolved) if resolved.starts_with(&root) => return Ok(()),
+			Ok(_) => break,
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => dir = current.parent(),
+			Err(e) => return Err(e.into()),
+		}
+	}
+	Err(LiveError::Load(FmtError::SandboxViolation))
+}
+
+/// Writes made by a controller, remembered so the watch events they cause are
+/// not mistaken for outside changes.
+#[derive(Default)]
+pub(crate) struct Writes {
+	/// Content hash of each written file, or `None` for deleted ones.
+	written: Mutex<HashMap<PathBuf, Option<u64>>>,
+	/// Serializes read-modify-write cycles.
+	lock: tokio::sync::Mutex<()>,
+}
+
+impl Writes {
+	/// Serializes writes of the controller while the guard is held.
+	pub async fn lock(&self) -> tokio::sync::MutexGuard<'_, ()> {
+		self.lock.lock().await
+	}
+
+	/// Replaces `path` with `bytes` atomically, keeping its permissions.
+	pub async fn write(&self, path: &Path, bytes: &[u8]) -> Result<(), LiveError> {
+		let name = path
+			.file_name()
+			.ok_or_else(|| LiveError::Write(format!("{}: not a file", path.display())))?;
+		// Hidden, so directory scans skip it
+		let tmp = path.with_file_name(format!(".{}.tmp", name.to_string_lossy()));
+		let permissions = fs::metadata(path).await.ok().map(|m| m.permissions());
+		if let Some(parent) = path.parent() {
+			fs::create_dir_all(parent).await?;
+		}
+		self.remember(path, Some(hash(bytes))).await;
+		fs::write(&tmp, bytes).await?;
+		if let Some(permissions) = permissions {
+			fs::set_permissions(&tmp, permissions).await?;
+		}
+		if let Err(e) = fs::rename(&tmp, path).await {
+			let _ = fs::remove_file(&tmp).await;
+			return Err(e.into());
+		}
+		Ok(())
+	}
+
+	/// Deletes `path`.
+	pub async fn delete(&self, path: &Path) -> Result<(), LiveError> {
+		self.remember(path, None).await;
+		fs::remove_file(path).await?;
+		Ok(())
+	}
+
+	async fn remember(&self, path: &Path, hash: Option<u64>) {
+		// Watch events carry resolved paths
+		let path = match (path.parent(), path.file_name()) {
+			(Some(parent), Some(name)) => fs::canonicalize(match parent.as_os_str().is_empty() {
+				true => Path::new("."),
+				false => parent,
+			})
+			.await
+			.map_or_else(|_| path.to_path_buf(), |parent| parent.join(name)),
+			_ => path.to_path_buf(),
+		};
+		let mut written = self.written.lock().unwrap_or_else(|e| e.into_inner());
+		written.insert(path, hash);
+	}
+
+	/// Returns true if `path` still is as this controller left it, so an event
+	/// for it needs no reload.
+	///
+	/// Paths that changed since are forgotten.
+	#[cfg(feature = "signal")]
+	pub async fn is_own(&self, path: &Path) -> bool {
+		let expected = {
+			let written = self.written.lock().unwrap_or_else(|e| e.into_inner());
+			match written.get(path) {
+				Some(expected) => *expected,
+				None => return false,
+			}
+		};
+		let actual = fs::read(path).await.ok().map(|bytes| hash(&bytes));
+		if actual == expected {
+			return true;
+		}
+		let mut written = self.written.lock().unwrap_or_else(|e| e.into_inner());
+		written.remove(path);
+		false
+	}
+
+	/// Returns true if there are `paths` and all of them are as this controller
+	/// left them.
+	#[cfg(feature = "signal")]
+	pub async fn all_own(&self, paths: &[PathBuf]) -> bool {
+		if paths.is_empty() {
+			return false;
+		}
+		for path in paths {
+			if !self.is_own(path).await {
+				return false;
+			}
+		}
+		true
+	}
+}
+
+fn hash(bytes: &[u8]) -> u64 {
+	let mut hasher = DefaultHasher::new();
+	bytes.hash(&mut hasher);
+	hasher.finish()
+}
@@ -0,0 +1,140 @@
+/* tests/apply_hooks.rs */
+
+#![cfg(feature = "full")]
+
+mod common;
+
+use common::{ListenerConfig, listener, loader};
+use live::controller::{Live, LiveDir, LiveError};
+use live::holder::Store;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Pretends that binding `:1` fails.
+async fn bind(
+	_old: Option<Arc<ListenerConfig>>,
+	new: Arc<ListenerConfig>,
+) -> Result<(), std::io::Error> {
+	if new.bind == ":1" {
+		return Err(std::io::Error::other("address in use"));
+	}
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_live_rolls_back_rejected_value() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+	tokio::fs::write(dir_path.join("app.json"), br#"{"bind": ":443"}"#).await?;
+
+	let applied = Arc::new(Mutex::new(Vec::new()));
+	let applied_clone = applied.clone();
+	let store = Arc::new(Store::<ListenerConfig>::new());
+	let live = Live::builder()
+		.store(store.clone())
+		.loader(loader(dir_path))
+		.key("app")
+		.on_apply(move |old: Option<Arc<ListenerConfig>>, new| {
+			let old = old.map(|old| old.bind.clone());
+			applied_clone.lock().unwrap().push((old, new.bind.clone()));
+			bind(None, new)
+		})
+		.build()?;
+	live.load().await?;
+
+	tokio::fs::write(dir_path.join("app.json"), br#"{"bind": ":1"}"#).await?;
+	let err = live.reload().await.unwrap_err();
+	assert!(matches!(&err, LiveError::RolledBack(m) if m == "[app] address in use"));
+	assert_eq!(store.get("app").unwrap().bind, ":443");
+
+	// Unchanged content is retried on the next reload
+	assert!(live.reload().await.is_err());
+
+	tokio::fs::write(dir_path.join("app.json"), br#"{"bind": ":8443"}"#).await?;
+	live.reload().await?;
+	assert_eq!(store.get("app").unwrap().bind, ":8443");
+
+	let applied = applied.lock().unwrap();
+	assert_eq!(applied[0], (None, ":443".to_string()));
+	assert_eq!(applied[1], (Some(":443".to_string()), ":1".to_string()));
+	assert_eq!(applied.last().unwrap().0.as_deref(), Some(":443"));
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_live_dir_rolls_back_rejected_entries() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+	tokio::fs::write(dir_path.join("a.json"), br#"{"bind": ":443"}"#).await?;
+	tokio::fs::write(dir_path.join("b.json"), br#"{"bind": ":80"}"#).await?;
+
+	let live_dir = LiveDir::builder()
+		.store(Arc::new(Store::<ListenerConfig>::new()))
+		.loader(loader(dir_path))
+		.path(dir_path)
+		.on_apply(bind)
+		.build()?;
+	live_dir.load().await?;
+
+	tokio::fs::write(dir_path.join("a.json"), br#"{"bind": ":1"}"#).await?;
+	tokio::fs::write(dir_path.join("b.json"), br#"{"bind": ":8080"}"#).await?;
+	tokio::fs::write(dir_path.join("c.json"), br#"{"bind": ":1"}"#).await?;
+	let result = live_dir.reload().await?;
+
+	assert_eq!(result.updated, vec!["b"]);
+	assert!(result.added.is_empty());
+	assert_eq!(
+		result.rolled_back,
+		vec![
+			("a".to_string(), "address in use".to_string()),
+			("c".to_string(), "address in use".to_string()),
+		]
+	);
+	assert_eq!(live_dir.get("a").unwrap().bind, ":443");
+	assert_eq!(live_dir.get("b").unwrap().bind, ":8080");
+	assert!(live_dir.get("c").is_none());
+
+	let mut keys = live_dir.keys().await;
+	keys.sort();
+	assert_eq!(keys, vec!["a", "b"]);
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_rollback_keeps_concurrent_override() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+	tokio::fs::write(dir_path.join("app.json"), br#"{"bind": ":443"}"#).await?;
+
+	let live = Live::builder()
+		.store(Arc::new(Store::<ListenerConfig>::new()))
+		.loader(loader(dir_path))
+		.key("app")
+		.on_apply(|old, new| async move {
+			// Slow to fail, so the override lands while `:1` is being applied
+			if new.bind == ":1" {
+				tokio::time::sleep(Duration::from_millis(200)).await;
+			}
+			bind(old, new).await
+		})
+		.build()?;
+	live.load().await?;
+
+	tokio::fs::write(dir_path.join("app.json"), br#"{"bind": ":1"}"#).await?;
+	let reload = tokio::spawn({
+		let live = live.clone();
+		async move { live.reload().await }
+	});
+	tokio::time::sleep(Duration::from_millis(50)).await;
+	live.set_override(listener(":9"))?;
+	assert!(matches!(reload.await?, Err(LiveError::RolledBack(_))));
+
+	// The override stays, and the rejected value is not what it restores
+	assert_eq!(live.get().unwrap().bind, ":9");
+	assert!(live.clear_override());
+	assert_eq!(live.get().unwrap().bind, ":443");
+
+	Ok(())
+}
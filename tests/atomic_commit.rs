@@ -0,0 +1,157 @@
+/* tests/atomic_commit.rs */
+
+#![cfg(feature = "full")]
+
+mod common;
+
+use common::loader;
+use live::controller::{CommitMode, LiveDir};
+use live::holder::{HoldEvent, Store, UnloadPolicy};
+use live::loader::PreProcess;
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Deserialize, PartialEq, validator::Validate)]
+struct ListenerConfig {
+	bind: String,
+	#[serde(default)]
+	upstream: Option<String>,
+}
+
+impl PreProcess for ListenerConfig {}
+
+#[tokio::test]
+async fn test_atomic_scan_rejects_all_on_failure() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+	tokio::fs::write(dir_path.join("443.json"), br#"{"bind": ":443"}"#).await?;
+	tokio::fs::write(dir_path.join("80.json"), br#"{"bind": ":80"}"#).await?;
+
+	let live_dir = LiveDir::builder()
+		.store(Arc::new(Store::<ListenerConfig>::new()))
+		.loader(loader(dir_path))
+		.path(dir_path)
+		.commit_mode(CommitMode::Atomic)
+		.build()?;
+	let result = live_dir.load().await?;
+	assert_eq!(result.added, vec!["443", "80"]);
+
+	// 443 now depends on an upstream that fails to load
+	tokio::fs::write(
+		dir_path.join("443.json"),
+		br#"{"bind": ":443", "upstream": "8080"}"#,
+	)
+	.await?;
+	tokio::fs::write(dir_path.join("8080.json"), b"{").await?;
+	tokio::fs::remove_file(dir_path.join("80.json")).await?;
+
+	let result = live_dir.reload().await?;
+	assert!(result.updated.is_empty());
+	assert!(result.removed.is_empty());
+	assert_eq!(result.failed.len(), 2);
+	assert_eq!(result.failed[0].0, "443");
+	assert_eq!(result.failed[0].1, "rejected: 8080 failed");
+	assert_eq!(result.failed[1].0, "8080");
+
+	// Nothing was applied
+	assert_eq!(live_dir.get("443").unwrap().upstream, None);
+	assert!(live_dir.get("80").is_some());
+	assert!(live_dir.get("8080").is_none());
+
+	tokio::fs::write(dir_path.join("8080.json"), br#"{"bind": ":8080"}"#).await?;
+	let result = live_dir.reload().await?;
+	assert!(result.failed.is_empty());
+	assert_eq!(result.added, vec!["8080"]);
+	assert_eq!(result.updated, vec!["443"]);
+	assert_eq!(result.removed, vec!["80"]);
+	assert_eq!(
+		live_dir.get("443").unwrap().upstream.as_deref(),
+		Some("8080")
+	);
+	assert!(live_dir.get("80").is_none());
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_atomic_scan_keeps_other_keys() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+	tokio::fs::write(dir_path.join("a.json"), br#"{"bind": "a"}"#).await?;
+
+	let store = Arc::new(Store::<ListenerConfig>::new());
+	store.insert(
+		"other".to_string(),
+		ListenerConfig {
+			bind: "other".to_string(),
+			upstream: None,
+		},
+		dir_path.join("elsewhere.json"),
+		UnloadPolicy::default(),
+	);
+
+	let live_dir = LiveDir::builder()
+		.store(store.clone())
+		.loader(loader(dir_path))
+		.path(dir_path)
+		.policy(UnloadPolicy::Persistent)
+		.commit_mode(CommitMode::Atomic)
+		.build()?;
+	live_dir.load().await?;
+
+	tokio::fs::remove_file(dir_path.join("a.json")).await?;
+	tokio::fs::write(dir_path.join("b.json"), br#"{"bind": "b"}"#).await?;
+	let result = live_dir.reload().await?;
+
+	assert_eq!(result.added, vec!["b"]);
+	assert_eq!(result.retained, vec!["a"]);
+	assert_eq!(store.get("other").unwrap().bind, "other");
+	assert_eq!(store.get("a").unwrap().bind, "a");
+	assert_eq!(store.get("b").unwrap().bind, "b");
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_atomic_scan_publishes_single_change_only() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+	tokio::fs::write(dir_path.join("a.json"), br#"{"bind": "a"}"#).await?;
+	tokio::fs::write(dir_path.join("b.json"), br#"{"bind": "b"}"#).await?;
+
+	let store = Arc::new(Store::<ListenerConfig>::new());
+	store.insert(
+		"other".to_string(),
+		ListenerConfig {
+			bind: "other".to_string(),
+			upstream: None,
+		},
+		dir_path.join("elsewhere.json"),
+		UnloadPolicy::default(),
+	);
+	let live_dir = LiveDir::builder()
+		.store(store.clone())
+		.loader(loader(dir_path))
+		.path(dir_path)
+		.commit_mode(CommitMode::Atomic)
+		.build()?;
+	live_dir.load().await?;
+	let (b, other) = (store.get("b").unwrap(), store.get("other").unwrap());
+
+	let mut events = store.subscribe();
+	tokio::fs::write(dir_path.join("a.json"), br#"{"bind": "a2"}"#).await?;
+	let result = live_dir.reload().await?;
+	assert_eq!(result.updated, vec!["a"]);
+
+	assert!(Arc::ptr_eq(&store.get("b").unwrap(), &b));
+	assert!(Arc::ptr_eq(&store.get("other").unwrap(), &other));
+	let mut keys = Vec::new();
+	while let Ok(event) = events.try_recv() {
+		if let HoldEvent::Updated { key, .. } = event {
+			keys.push(key);
+		}
+	}
+	assert_eq!(keys, vec!["a"]);
+
+	Ok(())
+}
@@ -0,0 +1,91 @@
+/* tests/common/mod.rs */
+
+//!
+//! Fixtures shared by the integration tests.
+
+// Every test binary uses a different subset
+#![allow(dead_code)]
+
+use live::loader::{DynLoader, FileSource, PreProcess, format::AnyFormat};
+use serde::{Deserialize, Serialize};
+use std::ffi::OsStr;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::sync::{Mutex, MutexGuard};
+
+/// A listener entry, the config most `LiveDir` tests manage.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, validator::Validate)]
+pub struct ListenerConfig {
+	#[validate(length(min = 1))]
+	pub bind: String,
+	#[serde(default)]
+	pub tls: bool,
+	#[serde(default)]
+	pub workers: u32,
+}
+
+impl PreProcess for ListenerConfig {}
+
+pub fn listener(bind: &str) -> ListenerConfig {
+	ListenerConfig {
+		bind: bind.to_string(),
+		tls: false,
+		workers: 1,
+	}
+}
+
+/// A config with a single port, used by the watch tests.
+#[derive(Debug, Clone, Deserialize, PartialEq, validator::Validate)]
+pub struct AppConfig {
+	#[validate(range(min = 1))]
+	pub port: u16,
+}
+
+impl PreProcess for AppConfig {}
+
+/// Loads JSON, TOML and YAML files from `dir`.
+pub fn loader(dir: impl Into<PathBuf>) -> DynLoader {
+	DynLoader::builder()
+		.source(FileSource::new(dir))
+		.format(AnyFormat::Json)
+		.format(AnyFormat::Toml)
+		.format(AnyFormat::Yaml)
+		.build()
+		.unwrap()
+}
+
+/// A watcher that never reports anything, like one on a network mount.
+pub fn deaf() -> live::signal::Config {
+	live::signal::Config {
+		listen_events: Some(Vec::new()),
+		..Default::default()
+	}
+}
+
+/// Waits up to ten seconds for `done`, returning whether it happened.
+pub async fn wait_until(mut done: impl FnMut() -> bool) -> bool {
+	for _ in 0..100 {
+		if done() {
+			return true;
+		}
+		tokio::time::sleep(Duration::from_millis(100)).await;
+	}
+	false
+}
+
+static ENV: Mutex<()> = Mutex::const_new(());
+
+/// Serializes the tests of a binary that read or write environment variables.
+///
+/// Hold the guard for the whole test: loads with env overrides, interpolation
+/// or a profile variable read the environment from other threads.
+pub async fn lock_env() -> MutexGuard<'static, ()> {
+	ENV.lock().await
+}
+
+/// Sets an environment variable while the environment is locked.
+pub fn set_var(_env: &MutexGuard<'static, ()>, name: &str, value: impl AsRef<OsStr>) {
+	// SAFETY: every test of the binary that reads or writes the environment
+	// holds the guard, so no other thread accesses it concurrently.
+	unsafe { std::env::set_var(name, value) };
+}
@@ -0,0 +1,96 @@
+/* tests/diff.rs */
+
+#![cfg(feature = "full")]
+
+mod common;
+
+use common::loader;
+use live::controller::{Live, LiveDir};
+use live::holder::Store;
+use live::loader::PreProcess;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Deserialize, PartialEq, validator::Validate)]
+struct ListenerConfig {
+	bind: String,
+	#[serde(default)]
+	tls: bool,
+	#[serde(default)]
+	alpn: Vec<String>,
+}
+
+impl PreProcess for ListenerConfig {}
+
+#[tokio::test]
+async fn test_live_reload_returns_diff() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+	tokio::fs::write(
+		dir_path.join("listener.json"),
+		br#"{"bind": ":443", "tls": false, "alpn": ["h2"]}"#,
+	)
+	.await?;
+
+	let live = Live::builder()
+		.store(Arc::new(Store::<ListenerConfig>::new()))
+		.loader(loader(dir_path))
+		.key("listener")
+		.build()?;
+	live.load().await?;
+
+	tokio::fs::write(
+		dir_path.join("listener.json"),
+		br#"{"bind": ":443", "tls": true, "alpn": ["h2", "http/1.1"]}"#,
+	)
+	.await?;
+	let diff = live.reload().await?;
+
+	assert_eq!(diff.changes.len(), 2);
+	let tls = diff.get("tls").unwrap();
+	assert_eq!(tls.old, Some(json!(false)));
+	assert_eq!(tls.new, Some(json!(true)));
+	assert_eq!(tls.to_string(), "tls false → true");
+	assert!(diff.get("alpn[1]").unwrap().is_added());
+
+	// Reloading an unchanged file yields an empty diff
+	assert!(live.reload().await?.is_empty());
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_live_dir_scan_result_diffs() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+	tokio::fs::write(
+		dir_path.join("443.json"),
+		br#"{"bind": ":443", "tls": false}"#,
+	)
+	.await?;
+	tokio::fs::write(dir_path.join("80.json"), br#"{"bind": ":80"}"#).await?;
+
+	let live_dir = LiveDir::builder()
+		.store(Arc::new(Store::<ListenerConfig>::new()))
+		.loader(loader(dir_path))
+		.path(dir_path)
+		.build()?;
+
+	let result = live_dir.load().await?;
+	assert_eq!(result.added.len(), 2);
+	assert!(result.diffs.is_empty());
+
+	tokio::fs::write(
+		dir_path.join("443.json"),
+		br#"{"bind": ":443", "tls": true}"#,
+	)
+	.await?;
+	let result = live_dir.reload().await?;
+
+	assert_eq!(result.diff("443").unwrap().to_string(), "tls false → true");
+	assert!(result.diff("80").is_none());
+	assert_eq!(result.unchanged, vec!["80"]);
+
+	Ok(())
+}
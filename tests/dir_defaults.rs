@@ -0,0 +1,155 @@
+/* tests/dir_defaults.rs */
+
+#![cfg(feature = "full")]
+
+mod common;
+
+use common::loader;
+use live::controller::{KeyPattern, LiveDir, ScanMode};
+use live::holder::Store;
+use live::loader::PreProcess;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Deserialize, PartialEq, validator::Validate)]
+struct ListenerConfig {
+	protocol: String,
+	bind: String,
+	timeout: u32,
+}
+
+impl PreProcess for ListenerConfig {}
+
+#[tokio::test]
+async fn test_defaults_file_subdirs() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+
+	tokio::fs::write(
+		dir_path.join("_defaults.json"),
+		br#"{"protocol": "http", "timeout": 30}"#,
+	)
+	.await?;
+	tokio::fs::create_dir(dir_path.join("[443]")).await?;
+	tokio::fs::create_dir(dir_path.join("[80]")).await?;
+	tokio::fs::write(
+		dir_path.join("[443]").join("config.json"),
+		br#"{"protocol": "https", "bind": "0.0.0.0:443"}"#,
+	)
+	.await?;
+	tokio::fs::write(
+		dir_path.join("[80]").join("config.toml"),
+		b"bind = \"0.0.0.0:80\"",
+	)
+	.await?;
+
+	let live_dir = LiveDir::builder()
+		.store(Arc::new(Store::<ListenerConfig>::new()))
+		.loader(loader(dir_path))
+		.path(dir_path)
+		.pattern(KeyPattern::Bracketed)
+		.scan_mode(ScanMode::Subdirs {
+			config_file: "config".to_string(),
+		})
+		.defaults_file("_defaults.json")
+		.build()?;
+
+	let result = live_dir.load().await?;
+	assert_eq!(result.loaded().count(), 2);
+	assert!(result.failed.is_empty());
+
+	let https = live_dir.get("443").unwrap();
+	assert_eq!(https.protocol, "https");
+	assert_eq!(https.timeout, 30);
+	let http = live_dir.get("80").unwrap();
+	assert_eq!(http.protocol, "http");
+	assert_eq!(http.timeout, 30);
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_defaults_file_excluded_from_keys() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+
+	tokio::fs::write(
+		dir_path.join("_defaults.toml"),
+		b"timeout = 10\nprotocol = \"tcp\"",
+	)
+	.await?;
+	tokio::fs::write(dir_path.join("a.json"), br#"{"bind": "a"}"#).await?;
+	tokio::fs::write(dir_path.join("b.json"), br#"{"bind": "b", "timeout": 5}"#).await?;
+
+	let live_dir = LiveDir::builder()
+		.store(Arc::new(Store::<ListenerConfig>::new()))
+		.loader(loader(dir_path))
+		.path(dir_path)
+		.defaults_file("_defaults")
+		.build()?;
+
+	let result = live_dir.load().await?;
+	assert!(result.failed.is_empty());
+
+	let mut keys = live_dir.keys().await;
+	keys.sort();
+	assert_eq!(keys, vec!["a", "b"]);
+	assert_eq!(live_dir.get("a").unwrap().timeout, 10);
+	assert_eq!(live_dir.get("b").unwrap().timeout, 5);
+
+	// Invalid defaults fail every entry and keep the old values
+	tokio::fs::write(dir_path.join("_defaults.toml"), b"not = = toml").await?;
+	let result = live_dir.reload().await?;
+	assert_eq!(result.failed.len(), 2);
+	assert!(result.failed[0].1.contains("_defaults"));
+	assert_eq!(live_dir.get("a").unwrap().timeout, 10);
+	assert_eq!(live_dir.len().await, 2);
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_defaults_file_change_reloads_all() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path().to_path_buf();
+
+	tokio::fs::write(
+		dir_path.join("_defaults.json"),
+		br#"{"protocol": "http", "timeout": 30}"#,
+	)
+	.await?;
+	tokio::fs::write(dir_path.join("a.json"), br#"{"bind": "a"}"#).await?;
+	tokio::fs::write(dir_path.join("b.json"), br#"{"bind": "b"}"#).await?;
+
+	let live_dir = LiveDir::builder()
+		.store(Arc::new(Store::<ListenerConfig>::new()))
+		.loader(loader(&dir_path))
+		.path(&dir_path)
+		.defaults_file("_defaults.json")
+		.build()?;
+
+	live_dir.load().await?;
+	let mut live_dir = live_dir.watch(live::signal::Config::default()).await?;
+
+	tokio::fs::write(
+		dir_path.join("_defaults.json"),
+		br#"{"protocol": "http", "timeout": 60}"#,
+	)
+	.await?;
+
+	for _ in 0..50 {
+		tokio::time::sleep(Duration::from_millis(100)).await;
+		if live_dir.get("a").unwrap().timeout == 60 && live_dir.get("b").unwrap().timeout == 60 {
+			break;
+		}
+	}
+
+	assert_eq!(live_dir.get("a").unwrap().timeout, 60);
+	assert_eq!(live_dir.get("b").unwrap().timeout, 60);
+	assert!(live_dir.get("_defaults").is_none());
+
+	live_dir.stop_watching();
+
+	Ok(())
+}
@@ -0,0 +1,173 @@
+/* tests/dir_extends.rs */
+
+#![cfg(feature = "full")]
+
+mod common;
+
+use common::loader;
+use live::controller::{Inheritance, KeyPattern, LiveDir, ScanMode};
+use live::holder::Store;
+use live::loader::PreProcess;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Deserialize, PartialEq, validator::Validate)]
+struct ListenerConfig {
+	protocol: String,
+	bind: String,
+	timeout: u32,
+}
+
+impl PreProcess for ListenerConfig {}
+
+#[tokio::test]
+async fn test_extends_chain_with_templates() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+
+	tokio::fs::write(
+		dir_path.join("_base.json"),
+		br#"{"protocol": "http", "timeout": 30}"#,
+	)
+	.await?;
+	tokio::fs::write(
+		dir_path.join("_base-https.toml"),
+		b"extends = \"base\"\nprotocol = \"https\"",
+	)
+	.await?;
+	tokio::fs::write(
+		dir_path.join("443.json"),
+		br#"{"extends": "base-https", "bind": "0.0.0.0:443"}"#,
+	)
+	.await?;
+	tokio::fs::write(
+		dir_path.join("8443.json"),
+		br#"{"extends": "443", "bind": "0.0.0.0:8443", "timeout": 5}"#,
+	)
+	.await?;
+
+	let live_dir = LiveDir::builder()
+		.store(Arc::new(Store::<ListenerConfig>::new()))
+		.loader(loader(dir_path))
+		.path(dir_path)
+		.inheritance(Inheritance::default())
+		.build()?;
+
+	let result = live_dir.load().await?;
+	assert!(result.failed.is_empty(), "{:?}", result.failed);
+
+	let mut keys = live_dir.keys().await;
+	keys.sort();
+	assert_eq!(keys, vec!["443", "8443"]);
+
+	let https = live_dir.get("443").unwrap();
+	assert_eq!(https.protocol, "https");
+	assert_eq!(https.timeout, 30);
+	let alt = live_dir.get("8443").unwrap();
+	assert_eq!(alt.protocol, "https");
+	assert_eq!(alt.bind, "0.0.0.0:8443");
+	assert_eq!(alt.timeout, 5);
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_extends_cycles_and_missing_parents() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+
+	for (name, parent) in [("[a]", "b"), ("[b]", "a"), ("[c]", "nope")] {
+		tokio::fs::create_dir(dir_path.join(name)).await?;
+		tokio::fs::write(
+			dir_path.join(name).join("config.json"),
+			format!(
+				r#"{{"extends": "{}", "protocol": "tcp", "bind": "x", "timeout": 1}}"#,
+				parent
+			),
+		)
+		.await?;
+	}
+	tokio::fs::create_dir(dir_path.join("[ok]")).await?;
+	tokio::fs::write(
+		dir_path.join("[ok]").join("config.json"),
+		br#"{"protocol": "tcp", "bind": "y", "timeout": 1}"#,
+	)
+	.await?;
+
+	let live_dir = LiveDir::builder()
+		.store(Arc::new(Store::<ListenerConfig>::new()))
+		.loader(loader(dir_path))
+		.path(dir_path)
+		.pattern(KeyPattern::Bracketed)
+		.scan_mode(ScanMode::Subdirs {
+			config_file: "config".to_string(),
+		})
+		.inheritance(Inheritance::default())
+		.build()?;
+
+	let result = live_dir.load().await?;
+	assert_eq!(result.loaded().collect::<Vec<_>>(), vec!["ok"]);
+
+	let mut failed = result.failed.clone();
+	failed.sort();
+	assert_eq!(failed.len(), 3);
+	assert!(failed[0].1.contains("inheritance cycle: a -> b -> a"));
+	assert!(failed[1].1.contains("inheritance cycle: b -> a -> b"));
+	assert!(failed[2].1.contains("missing parent `nope`"));
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_parent_change_reloads_dependents() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path().to_path_buf();
+
+	tokio::fs::write(
+		dir_path.join("_base.json"),
+		br#"{"protocol": "http", "timeout": 30}"#,
+	)
+	.await?;
+	tokio::fs::write(
+		dir_path.join("a.json"),
+		br#"{"extends": "base", "bind": "a"}"#,
+	)
+	.await?;
+	tokio::fs::write(
+		dir_path.join("b.json"),
+		br#"{"extends": "base", "bind": "b"}"#,
+	)
+	.await?;
+
+	let live_dir = LiveDir::builder()
+		.store(Arc::new(Store::<ListenerConfig>::new()))
+		.loader(loader(&dir_path))
+		.path(&dir_path)
+		.inheritance(Inheritance::default())
+		.build()?;
+
+	live_dir.load().await?;
+	let mut live_dir = live_dir.watch(live::signal::Config::default()).await?;
+
+	tokio::fs::write(
+		dir_path.join("_base.json"),
+		br#"{"protocol": "http", "timeout": 60}"#,
+	)
+	.await?;
+
+	for _ in 0..50 {
+		tokio::time::sleep(Duration::from_millis(100)).await;
+		if live_dir.get("a").unwrap().timeout == 60 && live_dir.get("b").unwrap().timeout == 60 {
+			break;
+		}
+	}
+
+	assert_eq!(live_dir.get("a").unwrap().timeout, 60);
+	assert_eq!(live_dir.get("b").unwrap().timeout, 60);
+	assert!(live_dir.get("base").is_none());
+
+	live_dir.stop_watching();
+
+	Ok(())
+}
@@ -0,0 +1,87 @@
+/* tests/dir_incremental.rs */
+
+#![cfg(feature = "full")]
+
+mod common;
+
+use common::{ListenerConfig, loader, lock_env, set_var, wait_until};
+use live::controller::LiveDir;
+use live::holder::Store;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_watch_reloads_only_changed_entry() -> Result<(), Box<dyn std::error::Error>> {
+	let env = lock_env().await;
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path().to_path_buf();
+	set_var(&env, "LIVE_INCR_A", ":443");
+	set_var(&env, "LIVE_INCR_B", ":80");
+	tokio::fs::write(dir_path.join("a.json"), br#"{"bind": "${LIVE_INCR_A}"}"#).await?;
+	tokio::fs::write(dir_path.join("b.json"), br#"{"bind": "${LIVE_INCR_B}"}"#).await?;
+
+	let live_dir = LiveDir::builder()
+		.store(Arc::new(Store::<ListenerConfig>::new()))
+		.loader(loader(&dir_path))
+		.path(&dir_path)
+		.interpolate(true)
+		.build()?;
+	live_dir.load().await?;
+	let mut live_dir = live_dir.watch(live::signal::Config::default()).await?;
+
+	// A full rescan would pick up the new variable for `b` as well
+	set_var(&env, "LIVE_INCR_B", ":8080");
+	tokio::fs::write(
+		dir_path.join("a.json"),
+		br#"{"bind": "${LIVE_INCR_A}", "tls": true}"#,
+	)
+	.await?;
+
+	wait_until(|| live_dir.get("a").unwrap().tls).await;
+	tokio::time::sleep(Duration::from_millis(300)).await;
+	assert!(live_dir.get("a").unwrap().tls);
+	assert_eq!(live_dir.get("b").unwrap().bind, ":80");
+
+	// Touching `b` reloads it
+	tokio::fs::write(dir_path.join("b.json"), br#"{"bind": "${LIVE_INCR_B}"}"#).await?;
+	wait_until(|| live_dir.get("b").unwrap().bind == ":8080").await;
+	assert_eq!(live_dir.get("b").unwrap().bind, ":8080");
+
+	live_dir.stop_watching();
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_watch_adds_and_removes_entries() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path().to_path_buf();
+	tokio::fs::write(dir_path.join("a.json"), br#"{"bind": ":443"}"#).await?;
+	tokio::fs::write(dir_path.join("b.json"), br#"{"bind": ":80"}"#).await?;
+
+	let store = Arc::new(Store::<ListenerConfig>::new());
+	let live_dir = LiveDir::builder()
+		.store(store.clone())
+		.loader(loader(&dir_path))
+		.path(&dir_path)
+		.build()?;
+	live_dir.load().await?;
+	let version = store.get_meta("b").unwrap().version;
+	let mut live_dir = live_dir.watch(live::signal::Config::default()).await?;
+
+	tokio::fs::remove_file(dir_path.join("a.json")).await?;
+	tokio::fs::write(dir_path.join("c.json"), br#"{"bind": ":8443"}"#).await?;
+
+	wait_until(|| live_dir.get("a").is_none() && live_dir.get("c").is_some()).await;
+	assert!(live_dir.get("a").is_none());
+	assert_eq!(live_dir.get("c").unwrap().bind, ":8443");
+
+	let mut keys = live_dir.keys().await;
+	keys.sort();
+	assert_eq!(keys, vec!["b", "c"]);
+	assert_eq!(store.get_meta("b").unwrap().version, version);
+
+	live_dir.stop_watching();
+
+	Ok(())
+}
@@ -0,0 +1,152 @@
+/* tests/env_overrides.rs */
+
+#![cfg(feature = "full")]
+
+mod common;
+
+use common::{ListenerConfig, loader, lock_env, set_var};
+use live::controller::{EnvOverrides, KeyPattern, Live, LiveDir, ScanMode};
+use live::holder::Store;
+use live::loader::PreProcess;
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Deserialize, PartialEq, validator::Validate)]
+struct Db {
+	host: String,
+	port: u16,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, validator::Validate)]
+struct AppConfig {
+	db: Db,
+}
+
+impl PreProcess for AppConfig {}
+
+#[tokio::test]
+async fn test_live_env_overrides_and_reload() -> Result<(), Box<dyn std::error::Error>> {
+	let env = lock_env().await;
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+	tokio::fs::write(
+		dir_path.join("app.json"),
+		br#"{"db": {"host": "localhost", "port": 5432}}"#,
+	)
+	.await?;
+
+	let live = Live::builder()
+		.store(Arc::new(Store::<AppConfig>::new()))
+		.loader(loader(dir_path))
+		.key("app")
+		.env(EnvOverrides::new("LIVE_TEST_ENV_APP"))
+		.build()?;
+
+	set_var(&env, "LIVE_TEST_ENV_APP__DB__PORT", "5433");
+	live.load().await?;
+	assert_eq!(live.get().unwrap().db.port, 5433);
+	assert_eq!(live.get().unwrap().db.host, "localhost");
+
+	// File edit combined with an environment change on manual reload
+	tokio::fs::write(
+		dir_path.join("app.json"),
+		br#"{"db": {"host": "db.internal", "port": 5432}}"#,
+	)
+	.await?;
+	set_var(&env, "LIVE_TEST_ENV_APP__DB__PORT", "6000");
+	live.reload().await?;
+
+	assert_eq!(live.get().unwrap().db.port, 6000);
+	assert_eq!(live.get().unwrap().db.host, "db.internal");
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_live_dir_env_overrides_per_entry() -> Result<(), Box<dyn std::error::Error>> {
+	let env = lock_env().await;
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+
+	tokio::fs::create_dir(dir_path.join("[443]")).await?;
+	tokio::fs::create_dir(dir_path.join("[80]")).await?;
+	tokio::fs::write(
+		dir_path.join("[443]").join("config.json"),
+		br#"{"bind": "0.0.0.0:443"}"#,
+	)
+	.await?;
+	tokio::fs::write(
+		dir_path.join("[80]").join("config.json"),
+		br#"{"bind": "0.0.0.0:80"}"#,
+	)
+	.await?;
+
+	let live_dir = LiveDir::builder()
+		.store(Arc::new(Store::<ListenerConfig>::new()))
+		.loader(loader(dir_path))
+		.path(dir_path)
+		.pattern(KeyPattern::Bracketed)
+		.scan_mode(ScanMode::Subdirs {
+			config_file: "config".to_string(),
+		})
+		.env(EnvOverrides::new("LIVE_TEST_ENV_DIR__LISTENERS"))
+		.build()?;
+
+	set_var(
+		&env,
+		"LIVE_TEST_ENV_DIR__LISTENERS__443__BIND",
+		"127.0.0.1:8443",
+	);
+	set_var(&env, "LIVE_TEST_ENV_DIR__LISTENERS__443__TLS", "true");
+
+	let result = live_dir.load().await?;
+	assert!(result.failed.is_empty());
+
+	let https = live_dir.get("443").unwrap();
+	assert_eq!(https.bind, "127.0.0.1:8443");
+	assert!(https.tls);
+	assert_eq!(live_dir.get("80").unwrap().bind, "0.0.0.0:80");
+
+	Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_live_env_overrides_skip_non_utf8() -> Result<(), Box<dyn std::error::Error>> {
+	let env = lock_env().await;
+	use std::ffi::OsStr;
+	use std::os::unix::ffi::OsStrExt;
+
+	let dir = tempfile::tempdir()?;
+	tokio::fs::write(
+		dir.path().join("app.json"),
+		br#"{"db": {"host": "localhost", "port": 5432}}"#,
+	)
+	.await?;
+
+	let live = Live::builder()
+		.store(Arc::new(Store::<AppConfig>::new()))
+		.loader(loader(dir.path()))
+		.key("app")
+		.env(EnvOverrides::new("LIVE_TEST_ENV_UTF8"))
+		.build()?;
+
+	set_var(
+		&env,
+		"LIVE_TEST_ENV_OTHER_BAD",
+		OsStr::from_bytes(b"\xff\xfe"),
+	);
+	set_var(
+		&env,
+		"LIVE_TEST_ENV_UTF8__DB__HOST",
+		OsStr::from_bytes(b"\xff\xfe"),
+	);
+	set_var(&env, "LIVE_TEST_ENV_UTF8__DB__PORT", "5433");
+	live.load().await?;
+
+	let config = live.get().unwrap();
+	assert_eq!(config.db.port, 5433);
+	assert_eq!(config.db.host, "localhost");
+
+	Ok(())
+}
@@ -0,0 +1,112 @@
+/* tests/field_edit.rs */
+
+#![cfg(feature = "full")]
+
+mod common;
+
+use common::loader;
+use live::controller::{Live, LiveDir};
+use live::holder::Store;
+use live::loader::PreProcess;
+use serde::Deserialize;
+use std::sync::Arc;
+use validator::Validate;
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Validate)]
+struct DbConfig {
+	host: String,
+	#[validate(range(min = 1))]
+	port: u16,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Validate)]
+struct AppConfig {
+	name: String,
+	#[validate(nested)]
+	db: DbConfig,
+}
+
+impl PreProcess for AppConfig {}
+
+const TOML: &str = "# Service settings
+name = \"api\"
+
+[db]
+host = \"localhost\" # primary
+port = 5432
+";
+
+#[tokio::test]
+async fn test_live_set_field_keeps_comments() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let file = dir.path().join("app.toml");
+	tokio::fs::write(&file, TOML).await?;
+
+	let live = Live::builder()
+		.store(Arc::new(Store::<AppConfig>::new()))
+		.loader(loader(dir.path()))
+		.key("app")
+		.root(dir.path())
+		.build()?;
+	live.load().await?;
+
+	let diff = live.set_field("db.port", 5433).await?;
+	assert!(diff.get("db.port").is_some());
+	assert_eq!(live.get().unwrap().db.port, 5433);
+	assert_eq!(
+		tokio::fs::read_to_string(&file).await?,
+		TOML.replace("5432", "5433")
+	);
+
+	live.set_field("db.host", "db.internal").await?;
+	let text = tokio::fs::read_to_string(&file).await?;
+	assert!(text.contains("host = \"db.internal\" # primary"));
+	assert!(text.starts_with("# Service settings\n"));
+
+	// Rejected edits are reverted
+	assert!(live.set_field("db.port", 0).await.is_err());
+	assert!(live.set_field("db.port", "x").await.is_err());
+	assert!(live.set_field("db.user", "x").await.is_err());
+	assert!(
+		tokio::fs::read_to_string(&file)
+			.await?
+			.contains("port = 5433")
+	);
+	assert_eq!(live.get().unwrap().db.port, 5433);
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_live_dir_set_field_yaml() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+	let yaml = "# api\nname: api\ndb:\n  host: localhost # primary\n  port: 5432\n";
+	tokio::fs::write(dir_path.join("a.yaml"), yaml).await?;
+
+	let live_dir = LiveDir::builder()
+		.store(Arc::new(Store::<AppConfig>::new()))
+		.loader(loader(dir_path))
+		.path(dir_path)
+		.build()?;
+	live_dir.load().await?;
+
+	let result = live_dir.set_field("a", "db.port", 5433).await?;
+	assert_eq!(result.updated, vec!["a"]);
+	assert_eq!(live_dir.get("a").unwrap().db.port, 5433);
+	assert_eq!(
+		tokio::fs::read_to_string(dir_path.join("a.yaml")).await?,
+		yaml.replace("5432", "5433")
+	);
+
+	assert!(live_dir.set_field("a", "db.port", 0).await.is_err());
+	assert!(live_dir.set_field("b", "db.port", 1).await.is_err());
+	assert_eq!(live_dir.get("a").unwrap().db.port, 5433);
+	assert!(
+		tokio::fs::read_to_string(dir_path.join("a.yaml"))
+			.await?
+			.contains("port: 5433")
+	);
+
+	Ok(())
+}
@@ -0,0 +1,136 @@
+/* tests/history.rs */
+
+#![cfg(feature = "full")]
+
+mod common;
+
+use common::{ListenerConfig, listener, loader};
+use live::controller::{Live, LiveDir, LiveError};
+use live::holder::Store;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_live_history_and_rollback() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+	let file = dir_path.join("app.json");
+
+	let live = Live::builder()
+		.store(Arc::new(Store::<ListenerConfig>::new()))
+		.loader(loader(dir_path))
+		.key("app")
+		.history_limit(2)
+		.build()?;
+	assert_eq!(live.current_generation(), 0);
+
+	for bind in [":1", ":2", ":3"] {
+		tokio::fs::write(&file, format!(r#"{{"bind": "{}"}}"#, bind)).await?;
+		live.reload().await?;
+	}
+	// Unchanged reloads do not start a generation
+	live.reload().await?;
+	assert_eq!(live.current_generation(), 3);
+
+	let history = live.history();
+	assert_eq!(history.len(), 2);
+	assert_eq!(history[0].generation, 2);
+	assert_eq!(history[0].value.bind, ":2");
+	assert!(history[0].source.ends_with("app.json"));
+	assert!(history[0].hash.is_some());
+
+	assert!(live.rollback_to(2)?);
+	assert_eq!(live.get().unwrap().bind, ":2");
+	assert_eq!(live.current_generation(), 4);
+	assert!(!live.rollback_to(4)?);
+
+	// The rollback sticks until the file changes
+	live.reload().await?;
+	assert_eq!(live.get().unwrap().bind, ":2");
+
+	assert!(matches!(
+		live.rollback_to(1),
+		Err(LiveError::UnknownGeneration(1))
+	));
+	assert!(live.rollback_to(9).is_err());
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_live_dir_rollback_to_generation() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+	tokio::fs::write(dir_path.join("a.json"), br#"{"bind": ":443"}"#).await?;
+	tokio::fs::write(dir_path.join("b.json"), br#"{"bind": ":80"}"#).await?;
+
+	let live_dir = LiveDir::builder()
+		.store(Arc::new(Store::<ListenerConfig>::new()))
+		.loader(loader(dir_path))
+		.path(dir_path)
+		.build()?;
+	live_dir.load().await?;
+	let good = live_dir.current_generation();
+	assert_eq!(good, 1);
+
+	// A bad push changes both entries at once
+	tokio::fs::write(dir_path.join("a.json"), br#"{"bind": ":1"}"#).await?;
+	tokio::fs::write(dir_path.join("b.json"), br#"{"bind": ":2"}"#).await?;
+	live_dir.reload().await?;
+	assert_eq!(live_dir.current_generation(), 2);
+	assert_eq!(live_dir.history("a")[1].generation, 2);
+
+	let restored = live_dir.rollback_to(good).await?;
+	assert_eq!(restored, vec!["a", "b"]);
+	assert_eq!(live_dir.get("a").unwrap().bind, ":443");
+	assert_eq!(live_dir.get("b").unwrap().bind, ":80");
+	assert_eq!(live_dir.current_generation(), 3);
+
+	// Entries whose file did not change keep the restored value
+	tokio::fs::write(dir_path.join("a.json"), br#"{"bind": ":8443"}"#).await?;
+	let result = live_dir.reload().await?;
+	assert_eq!(result.updated, vec!["a"]);
+	assert_eq!(live_dir.get("b").unwrap().bind, ":80");
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_rollback_skips_overridden_keys() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+	let file = dir_path.join("app.json");
+
+	let live = Live::builder()
+		.store(Arc::new(Store::<ListenerConfig>::new()))
+		.loader(loader(dir_path))
+		.key("app")
+		.build()?;
+	for bind in [":1", ":2"] {
+		tokio::fs::write(&file, format!(r#"{{"bind": "{}"}}"#, bind)).await?;
+		live.reload().await?;
+	}
+	live.set_override(listener(":9"))?;
+	assert!(!live.rollback_to(1)?);
+	assert_eq!(live.get().unwrap().bind, ":9");
+	assert!(matches!(
+		live.rollback_to(9),
+		Err(LiveError::UnknownGeneration(9))
+	));
+
+	let entries = dir_path.join("entries");
+	tokio::fs::create_dir(&entries).await?;
+	let live_dir = LiveDir::builder()
+		.store(Arc::new(Store::<ListenerConfig>::new()))
+		.loader(loader(&entries))
+		.path(&entries)
+		.build()?;
+	for bind in [":1", ":2"] {
+		tokio::fs::write(entries.join("a.json"), format!(r#"{{"bind": "{}"}}"#, bind)).await?;
+		live_dir.reload().await?;
+	}
+	live_dir.set_override("a", listener(":9"))?;
+	assert!(live_dir.rollback_to(1).await?.is_empty());
+	assert_eq!(live_dir.get("a").unwrap().bind, ":9");
+
+	Ok(())
+}
@@ -0,0 +1,188 @@
+/* tests/includes.rs */
+
+#![cfg(feature = "full")]
+
+mod common;
+
+use common::{loader, wait_until};
+use live::controller::{KeyPattern, Live, LiveDir, LiveError, ScanMode};
+use live::holder::Store;
+use live::loader::PreProcess;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Deserialize, PartialEq, validator::Validate)]
+struct Db {
+	host: String,
+	port: u16,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, validator::Validate)]
+struct AppConfig {
+	name: String,
+	db: Db,
+	#[serde(default)]
+	cache: HashMap<String, u32>,
+}
+
+impl PreProcess for AppConfig {}
+
+#[tokio::test]
+async fn test_live_includes_with_wildcards_and_watch() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+	tokio::fs::create_dir(dir_path.join("cache")).await?;
+	tokio::fs::write(
+		dir_path.join("app.json"),
+		br#"{"$include": ["db.toml", "cache/*.yaml"], "name": "app", "db": {"port": 6000}}"#,
+	)
+	.await?;
+	tokio::fs::write(
+		dir_path.join("db.toml"),
+		b"[db]\nhost = \"db.internal\"\nport = 5432",
+	)
+	.await?;
+	tokio::fs::write(dir_path.join("cache").join("a.yaml"), b"cache:\n  a: 1").await?;
+	tokio::fs::write(dir_path.join("cache").join("b.yaml"), b"cache:\n  b: 2").await?;
+
+	let live = Live::builder()
+		.store(Arc::new(Store::<AppConfig>::new()))
+		.loader(loader(dir_path))
+		.key("app")
+		.root(dir_path)
+		.build()?;
+
+	live.load().await?;
+	let config = live.get().unwrap();
+	assert_eq!(config.db.host, "db.internal");
+	// The including file wins over its includes
+	assert_eq!(config.db.port, 6000);
+	assert_eq!(config.cache.get("a"), Some(&1));
+	assert_eq!(config.cache.get("b"), Some(&2));
+
+	let live = live.watch(live::signal::Config::default()).await?;
+
+	// Editing an included file reloads the whole config
+	tokio::fs::write(dir_path.join("cache").join("b.yaml"), b"cache:\n  b: 20").await?;
+	assert!(wait_until(|| live.get().unwrap().cache.get("b") == Some(&20)).await);
+	assert_eq!(live.get().unwrap().name, "app");
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_include_cycle_and_sandbox() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+
+	tokio::fs::write(
+		dir_path.join("app.json"),
+		br#"{"$include": "a.json", "name": "app", "db": {"host": "h", "port": 1}}"#,
+	)
+	.await?;
+	tokio::fs::write(dir_path.join("a.json"), br#"{"$include": "b.json"}"#).await?;
+	tokio::fs::write(dir_path.join("b.json"), br#"{"$include": "./a.json"}"#).await?;
+
+	let live = Live::builder()
+		.store(Arc::new(Store::<AppConfig>::new()))
+		.loader(loader(dir_path))
+		.key("app")
+		.build()?;
+
+	let err = live.load().await.unwrap_err();
+	assert!(
+		matches!(err, LiveError::Include(ref m) if m.contains("include cycle: a.json -> b.json -> a.json")),
+		"{err}"
+	);
+
+	// Includes go through the sandboxed source
+	tokio::fs::write(
+		dir_path.join("app.json"),
+		br#"{"$include": "../outside.json", "name": "app", "db": {"host": "h", "port": 1}}"#,
+	)
+	.await?;
+	let err = live.load().await.unwrap_err();
+	assert!(matches!(err, LiveError::Include(_)), "{err}");
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_live_dir_entries_include_shared_file() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+
+	tokio::fs::write(
+		dir_path.join("shared.toml"),
+		b"[db]\nhost = \"shared\"\nport = 5432",
+	)
+	.await?;
+	for (name, app) in [("[a]", "a"), ("[b]", "b")] {
+		tokio::fs::create_dir(dir_path.join(name)).await?;
+		tokio::fs::write(
+			dir_path.join(name).join("config.json"),
+			format!(r#"{{"$include": "../shared.toml", "name": "{}"}}"#, app),
+		)
+		.await?;
+	}
+
+	let live_dir = LiveDir::builder()
+		.store(Arc::new(Store::<AppConfig>::new()))
+		.loader(loader(dir_path))
+		.path(dir_path)
+		.pattern(KeyPattern::Bracketed)
+		.scan_mode(ScanMode::Subdirs {
+			config_file: "config".to_string(),
+		})
+		.build()?;
+
+	let result = live_dir.load().await?;
+	assert!(result.failed.is_empty(), "{:?}", result.failed);
+	assert_eq!(live_dir.get("a").unwrap().db.host, "shared");
+	assert_eq!(live_dir.get("b").unwrap().name, "b");
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_live_dir_watches_defaults_includes() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+	tokio::fs::create_dir(dir_path.join("common")).await?;
+	tokio::fs::write(
+		dir_path.join("common").join("db.toml"),
+		b"[db]\nhost = \"shared\"\nport = 5432",
+	)
+	.await?;
+	tokio::fs::write(
+		dir_path.join("defaults.json"),
+		br#"{"$include": "common/db.toml"}"#,
+	)
+	.await?;
+	tokio::fs::create_dir(dir_path.join("a")).await?;
+	tokio::fs::write(dir_path.join("a").join("config.json"), br#"{"name": "a"}"#).await?;
+
+	let live_dir = LiveDir::builder()
+		.store(Arc::new(Store::<AppConfig>::new()))
+		.loader(loader(dir_path))
+		.path(dir_path)
+		.scan_mode(ScanMode::Subdirs {
+			config_file: "config".to_string(),
+		})
+		.defaults_file("defaults.json")
+		.build()?;
+	live_dir.load().await?;
+	assert_eq!(live_dir.get("a").unwrap().db.host, "shared");
+	let live_dir = live_dir.watch(live::signal::Config::default()).await?;
+
+	// Not an entry of its own, but included by the defaults of every entry
+	tokio::fs::write(
+		dir_path.join("common").join("db.toml"),
+		b"[db]\nhost = \"changed\"\nport = 5432",
+	)
+	.await?;
+	assert!(wait_until(|| live_dir.get("a").unwrap().db.host == "changed").await);
+
+	Ok(())
+}
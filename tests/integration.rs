@@ -4,9 +4,10 @@
 
 use live::controller::{KeyPattern, Live, LiveDir, ScanMode};
 use live::holder::{Store, UnloadPolicy};
-use live::loader::{DynLoader, FileSource, PreProcess, format::AnyFormat};
+use live::loader::{DynLoader, FileSource, FmtError, PreProcess, Source, format::AnyFormat};
 use serde::Deserialize;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 
 #[derive(Debug, Clone, Deserialize, PartialEq, validator::Validate)]
@@ -112,6 +113,75 @@ async fn test_live_dir_files_mode() -> Result<(), Box<dyn std::error::Error>> {
 	Ok(())
 }
 
+/// A file source that records how many reads are in flight at most.
+struct CountingSource {
+	inner: FileSource,
+	in_flight: AtomicUsize,
+	peak: Arc<AtomicUsize>,
+}
+
+#[async_trait::async_trait]
+impl Source for CountingSource {
+	async fn read(&self, key: &str) -> Result<Vec<u8>, FmtError> {
+		let current = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+		self.peak.fetch_max(current, Ordering::SeqCst);
+		// Keep the read pending long enough for others to start
+		tokio::time::sleep(Duration::from_millis(20)).await;
+		let result = self.inner.read(key).await;
+		self.in_flight.fetch_sub(1, Ordering::SeqCst);
+		result
+	}
+
+	async fn exists(&self, key: &str) -> bool {
+		self.inner.exists(key).await
+	}
+}
+
+#[tokio::test]
+async fn test_live_dir_concurrent_load_is_sorted() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+
+	for i in 0..40 {
+		tokio::fs::write(
+			dir_path.join(format!("{:02}.json", i)),
+			format!("{{\"val\": {}}}", i),
+		)
+		.await?;
+	}
+	tokio::fs::write(dir_path.join("bad.json"), b"{").await?;
+
+	let peak = Arc::new(AtomicUsize::new(0));
+	let loader = DynLoader::builder()
+		.source(CountingSource {
+			inner: FileSource::new(dir_path),
+			in_flight: AtomicUsize::new(0),
+			peak: Arc::clone(&peak),
+		})
+		.format(AnyFormat::Json)
+		.build()
+		.unwrap();
+
+	let live_dir = LiveDir::builder()
+		.store(Arc::new(Store::<TestConfig>::new()))
+		.loader(loader)
+		.path(dir_path)
+		.concurrency(4)
+		.build()?;
+
+	let result = live_dir.load().await?;
+	let expected: Vec<String> = (0..40).map(|i| format!("{:02}", i)).collect();
+	assert_eq!(result.added, expected);
+	assert_eq!(result.failed.len(), 1);
+	assert_eq!(live_dir.get("39").unwrap().val, 39);
+
+	// Reads overlapped, but never beyond the limit
+	let peak = peak.load(Ordering::SeqCst);
+	assert!(peak > 1 && peak <= 4, "peak of {} concurrent reads", peak);
+
+	Ok(())
+}
+
 #[tokio::test]
 async fn test_live_dir_subdirs_mode() -> Result<(), Box<dyn std::error::Error>> {
 	let dir = tempfile::tempdir()?;
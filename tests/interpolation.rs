@@ -0,0 +1,217 @@
+/* tests/interpolation.rs */
+
+#![cfg(feature = "full")]
+
+mod common;
+
+use common::{loader, lock_env, set_var, wait_until};
+use live::controller::{Live, LiveDir, LiveError};
+use live::holder::Store;
+use live::loader::PreProcess;
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Deserialize, PartialEq, validator::Validate)]
+struct Db {
+	host: String,
+	port: u16,
+	password: String,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, validator::Validate)]
+struct AppConfig {
+	db: Db,
+}
+
+impl PreProcess for AppConfig {}
+
+#[tokio::test]
+async fn test_live_interpolation_and_secret_rotation() -> Result<(), Box<dyn std::error::Error>> {
+	let env = lock_env().await;
+	let dir = tempfile::tempdir()?;
+	let secret = dir.path().join("secret.txt");
+	tokio::fs::write(
+		dir.path().join("app.toml"),
+		b"[db]\nhost = \"${LIVE_TEST_INTERP_HOST}\"\nport = \"${LIVE_TEST_INTERP_PORT:-5432}\"\npassword = \"${file:secret.txt}\"",
+	)
+	.await?;
+	tokio::fs::write(&secret, b"hunter2\n").await?;
+	set_var(&env, "LIVE_TEST_INTERP_HOST", "db.internal");
+
+	let live = Live::builder()
+		.store(Arc::new(Store::<AppConfig>::new()))
+		.loader(loader(dir.path()))
+		.key("app")
+		.root(dir.path())
+		.interpolate(true)
+		.build()?;
+
+	live.load().await?;
+	let config = live.get().unwrap();
+	assert_eq!(config.db.host, "db.internal");
+	assert_eq!(config.db.port, 5432);
+	assert_eq!(config.db.password, "hunter2");
+
+	let live = live.watch(live::signal::Config::default()).await?;
+
+	// Rotating the secret alone reloads the config embedding it
+	tokio::fs::write(&secret, b"correct-horse\n").await?;
+
+	for _ in 0..50 {
+		tokio::time::sleep(Duration::from_millis(100)).await;
+		if live.get().unwrap().db.password == "correct-horse" {
+			break;
+		}
+	}
+
+	assert_eq!(live.get().unwrap().db.password, "correct-horse");
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_live_watches_missing_secret() -> Result<(), Box<dyn std::error::Error>> {
+	let _env = lock_env().await;
+	let dir = tempfile::tempdir()?;
+	tokio::fs::write(
+		dir.path().join("app.json"),
+		br#"{"db": {"host": "h", "port": 1, "password": "${file:secret.txt:-unset}"}}"#,
+	)
+	.await?;
+
+	let live = Live::builder()
+		.store(Arc::new(Store::<AppConfig>::new()))
+		.loader(loader(dir.path()))
+		.key("app")
+		.root(dir.path())
+		.interpolate(true)
+		.build()?;
+	live.load().await?;
+	assert_eq!(live.get().unwrap().db.password, "unset");
+	let live = live.watch(live::signal::Config::default()).await?;
+
+	tokio::fs::write(dir.path().join("secret.txt"), b"hunter2\n").await?;
+	assert!(wait_until(|| live.get().unwrap().db.password == "hunter2").await);
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_live_unresolved_reference() -> Result<(), Box<dyn std::error::Error>> {
+	let _env = lock_env().await;
+	let dir = tempfile::tempdir()?;
+	tokio::fs::write(
+		dir.path().join("app.json"),
+		br#"{"db": {"host": "h", "port": 1, "password": "${LIVE_TEST_INTERP_UNSET}"}}"#,
+	)
+	.await?;
+
+	let live = Live::builder()
+		.store(Arc::new(Store::<AppConfig>::new()))
+		.loader(loader(dir.path()))
+		.key("app")
+		.interpolate(true)
+		.build()?;
+
+	let err = live.load().await.unwrap_err();
+	assert!(
+		matches!(err, LiveError::Unresolved(ref r) if r.contains("${LIVE_TEST_INTERP_UNSET}")),
+		"{err}"
+	);
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_live_dir_secret_files() -> Result<(), Box<dyn std::error::Error>> {
+	let env = lock_env().await;
+	let dir = tempfile::tempdir()?;
+	let outside = tempfile::tempdir()?;
+	let dir_path = dir.path().to_path_buf();
+	tokio::fs::create_dir(dir_path.join("secrets")).await?;
+	let secret = dir_path.join("secrets").join("db_password");
+	tokio::fs::write(&secret, b"first").await?;
+	tokio::fs::write(outside.path().join("db_password"), b"stolen").await?;
+
+	tokio::fs::write(
+		dir_path.join("a.json"),
+		br#"{"db": {"host": "a", "port": "${LIVE_TEST_INTERP_DIR_PORT}", "password": "${file:secrets/db_password}"}}"#,
+	)
+	.await?;
+	// Files outside of the directory are not read
+	tokio::fs::write(
+		dir_path.join("c.json"),
+		format!(
+			r#"{{"db": {{"host": "c", "port": 1, "password": "${{file:{}}}"}}}}"#,
+			outside.path().join("db_password").display()
+		),
+	)
+	.await?;
+	tokio::fs::write(
+		dir_path.join("b.json"),
+		br#"{"db": {"host": "b", "port": 1, "password": "${LIVE_TEST_INTERP_DIR_UNSET}"}}"#,
+	)
+	.await?;
+	set_var(&env, "LIVE_TEST_INTERP_DIR_PORT", "7000");
+
+	let errors = Arc::new(Mutex::new(Vec::new()));
+	let errors_clone = errors.clone();
+	let live_dir = LiveDir::builder()
+		.store(Arc::new(Store::<AppConfig>::new()))
+		.loader(loader(&dir_path))
+		.path(&dir_path)
+		.interpolate(true)
+		.on_error(move |e| errors_clone.lock().unwrap().push(e))
+		.build()?;
+
+	let result = live_dir.load().await?;
+	assert_eq!(result.loaded().collect::<Vec<_>>(), vec!["a"]);
+	let mut failed = result.failed.clone();
+	failed.sort();
+	assert_eq!(failed.len(), 2);
+	assert!(failed[0].1.contains("${LIVE_TEST_INTERP_DIR_UNSET}"));
+	assert!(failed[1].1.contains("outside of"), "{}", failed[1].1);
+
+	let config = live_dir.get("a").unwrap();
+	assert_eq!(config.db.port, 7000);
+	assert_eq!(config.db.password, "first");
+
+	let mut live_dir = live_dir.watch(live::signal::Config::default()).await?;
+
+	tokio::fs::write(&secret, b"second").await?;
+
+	for _ in 0..50 {
+		tokio::time::sleep(Duration::from_millis(100)).await;
+		if live_dir.get("a").unwrap().db.password == "second" {
+			break;
+		}
+	}
+
+	assert_eq!(live_dir.get("a").unwrap().db.password, "second");
+
+	// Touching the broken entry reports its error again
+	tokio::fs::write(
+		dir_path.join("b.json"),
+		br#"{"db": {"host": "b", "port": 2, "password": "${LIVE_TEST_INTERP_DIR_UNSET}"}}"#,
+	)
+	.await?;
+	let reported = || {
+		errors
+			.lock()
+			.unwrap()
+			.iter()
+			.any(|e| matches!(e, LiveError::Unresolved(r) if r.starts_with("[b]")))
+	};
+	for _ in 0..50 {
+		if reported() {
+			break;
+		}
+		tokio::time::sleep(Duration::from_millis(100)).await;
+	}
+	assert!(reported());
+
+	live_dir.stop_watching();
+
+	Ok(())
+}
@@ -0,0 +1,115 @@
+/* tests/last_good.rs */
+
+#![cfg(feature = "full")]
+
+mod common;
+
+use common::{ListenerConfig, loader};
+use live::controller::{Live, LiveDir, LoadStatus};
+use live::holder::Store;
+use std::sync::Arc;
+
+fn live(dir: &std::path::Path, cache: &std::path::Path) -> Live<ListenerConfig> {
+	Live::builder()
+		.store(Arc::new(Store::new()))
+		.loader(loader(dir))
+		.key("app")
+		.last_good(cache)
+		.build()
+		.unwrap()
+}
+
+#[tokio::test]
+async fn test_live_falls_back_to_last_good() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let cache = tempfile::tempdir()?;
+	let file = dir.path().join("app.json");
+	tokio::fs::write(&file, br#"{"bind": ":443"}"#).await?;
+
+	let status = live(dir.path(), cache.path()).load().await?;
+	assert_eq!(status, LoadStatus::Source);
+	assert!(cache.path().join("app.json").exists());
+
+	// A cold start with a corrupt file uses the cached value
+	tokio::fs::write(&file, b"{").await?;
+	let restarted = live(dir.path(), cache.path());
+	let status = restarted.load().await?;
+	assert!(status.is_cached());
+	assert_eq!(restarted.get().unwrap().bind, ":443");
+
+	// Without a cache the error surfaces
+	let empty = tempfile::tempdir()?;
+	assert!(live(dir.path(), empty.path()).load().await.is_err());
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_live_dir_falls_back_to_last_good() -> Result<(), Box<dyn std::error::Error>> {
+	let root = tempfile::tempdir()?;
+	let dir_path = root.path().join("listeners");
+	let cache = root.path().join("cache");
+	tokio::fs::create_dir(&dir_path).await?;
+	tokio::fs::write(dir_path.join("a.json"), br#"{"bind": ":443"}"#).await?;
+	tokio::fs::write(dir_path.join("b.json"), br#"{"bind": ":80"}"#).await?;
+
+	let build = || {
+		LiveDir::builder()
+			.store(Arc::new(Store::<ListenerConfig>::new()))
+			.loader(loader(&dir_path))
+			.path(&dir_path)
+			.last_good(&cache)
+			.build()
+	};
+	let result = build()?.load().await?;
+	assert!(result.cached.is_empty());
+
+	tokio::fs::write(dir_path.join("a.json"), b"{").await?;
+	let live_dir = build()?;
+	let result = live_dir.load().await?;
+	assert_eq!(result.loaded().collect::<Vec<_>>(), vec!["b"]);
+	assert_eq!(result.failed.len(), 1);
+	assert_eq!(result.cached.len(), 1);
+	assert_eq!(result.cached[0].0, "a");
+	assert_eq!(live_dir.get("a").unwrap().bind, ":443");
+	assert_eq!(live_dir.len().await, 2);
+
+	// A missing directory serves every cached entry
+	tokio::fs::remove_dir_all(&dir_path).await?;
+	let live_dir = build()?;
+	let result = live_dir.load().await?;
+	let cached: Vec<&str> = result.cached.iter().map(|(k, _)| k.as_str()).collect();
+	assert_eq!(cached, vec!["a", "b"]);
+	assert!(result.cached[0].1.contains("does not exist"));
+	assert_eq!(live_dir.get("b").unwrap().bind, ":80");
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_last_good_keeps_source_format() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let cache = tempfile::tempdir()?;
+	let file = dir.path().join("app.toml");
+	tokio::fs::write(&file, "bind = \":443\"\n").await?;
+
+	let build = || {
+		Live::<ListenerConfig>::builder()
+			.store(Arc::new(Store::new()))
+			.loader(loader(dir.path()))
+			.key("app")
+			.last_good(cache.path())
+			.build()
+	};
+	build()?.load().await?;
+	let cached = tokio::fs::read_to_string(cache.path().join("app.toml")).await?;
+	assert_eq!(cached.trim(), "bind = \":443\"");
+	assert!(!cache.path().join("app.json").exists());
+
+	tokio::fs::write(&file, "bind = ").await?;
+	let restarted = build()?;
+	assert!(restarted.load().await?.is_cached());
+	assert_eq!(restarted.get().unwrap().bind, ":443");
+
+	Ok(())
+}
@@ -0,0 +1,304 @@
+/* tests/layers.rs */
+
+#![cfg(feature = "full")]
+
+mod common;
+
+use common::{loader, lock_env, set_var};
+use live::controller::{Layer, Live};
+use live::holder::Store;
+use live::loader::PreProcess;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, validator::Validate)]
+struct Db {
+	host: String,
+	port: u16,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, validator::Validate)]
+struct AppConfig {
+	name: String,
+	debug: bool,
+	db: Db,
+}
+
+impl PreProcess for AppConfig {}
+
+fn defaults() -> AppConfig {
+	AppConfig {
+		name: "default".into(),
+		debug: false,
+		db: Db {
+			host: "localhost".into(),
+			port: 5432,
+		},
+	}
+}
+
+#[tokio::test]
+async fn test_layers_merge_in_order() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+
+	tokio::fs::write(
+		dir_path.join("app.json"),
+		br#"{"name": "base", "db": {"port": 6000}}"#,
+	)
+	.await?;
+	tokio::fs::write(
+		dir_path.join("app.local.toml"),
+		b"[db]\nhost = \"db.local\"",
+	)
+	.await?;
+
+	let live = Live::builder()
+		.store(Arc::new(Store::<AppConfig>::new()))
+		.loader(loader(dir_path))
+		.key("app")
+		.defaults(&defaults())
+		.layer(Layer::file("app.local"))
+		.layer(Layer::file("app.missing"))
+		.layer(Layer::value(&serde_json::json!({"debug": true}))?)
+		.build()?;
+
+	live.load().await?;
+
+	let config = live.get().unwrap();
+	assert_eq!(config.name, "base");
+	assert!(config.debug);
+	assert_eq!(config.db.host, "db.local");
+	assert_eq!(config.db.port, 6000);
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_layers_invalid_layer_keeps_old() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+
+	tokio::fs::write(dir_path.join("app.json"), br#"{"name": "base"}"#).await?;
+
+	let live = Live::builder()
+		.store(Arc::new(Store::<AppConfig>::new()))
+		.loader(loader(dir_path))
+		.key("app")
+		.defaults(&defaults())
+		.layer(Layer::file("override"))
+		.build()?;
+
+	live.load().await?;
+	assert_eq!(live.get().unwrap().name, "base");
+
+	tokio::fs::write(dir_path.join("override.json"), b"invalid json").await?;
+	let err = live.reload().await.unwrap_err();
+	assert!(err.to_string().contains("[override]"));
+	assert_eq!(live.get().unwrap().name, "base");
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_layers_watch_overlay_file() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+	let overlay = dir_path.join("overlay.json");
+	tokio::fs::write(dir_path.join("app.json"), br#"{"name": "base"}"#).await?;
+	tokio::fs::write(&overlay, br#"{"db": {"port": 1}}"#).await?;
+
+	let live = Live::builder()
+		.store(Arc::new(Store::<AppConfig>::new()))
+		.loader(loader(dir_path))
+		.key("app")
+		.root(dir_path)
+		.defaults(&defaults())
+		.layer(Layer::file("overlay"))
+		.build()?;
+
+	live.load().await?;
+	assert_eq!(live.get().unwrap().db.port, 1);
+
+	let live = live.watch(live::signal::Config::default()).await?;
+
+	// Editing the overlay alone must re-merge the whole stack
+	tokio::fs::write(&overlay, br#"{"db": {"port": 2}}"#).await?;
+
+	for _ in 0..50 {
+		tokio::time::sleep(Duration::from_millis(100)).await;
+		if live.get().unwrap().db.port == 2 {
+			break;
+		}
+	}
+
+	let config = live.get().unwrap();
+	assert_eq!(config.db.port, 2);
+	assert_eq!(config.name, "base");
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_profile_overlay() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+
+	tokio::fs::write(
+		dir_path.join("app.json"),
+		br#"{"name": "app", "debug": true, "db": {"host": "localhost", "port": 5432}}"#,
+	)
+	.await?;
+	tokio::fs::write(
+		dir_path.join("app.production.toml"),
+		b"debug = false\n[db]\nhost = \"db.prod\"",
+	)
+	.await?;
+	tokio::fs::write(dir_path.join("app.eu.json"), br#"{"db": {"port": 6543}}"#).await?;
+
+	let live = Live::builder()
+		.store(Arc::new(Store::<AppConfig>::new()))
+		.loader(loader(dir_path))
+		.key("app")
+		.profile("production")
+		.profile("eu")
+		.profile("missing")
+		.build()?;
+
+	live.load().await?;
+
+	let config = live.get().unwrap();
+	assert!(!config.debug);
+	assert_eq!(config.db.host, "db.prod");
+	assert_eq!(config.db.port, 6543);
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_profile_from_env() -> Result<(), Box<dyn std::error::Error>> {
+	let env = lock_env().await;
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+
+	tokio::fs::write(
+		dir_path.join("app.json"),
+		br#"{"name": "app", "debug": false, "db": {"host": "localhost", "port": 5432}}"#,
+	)
+	.await?;
+	tokio::fs::write(dir_path.join("app.staging.json"), br#"{"debug": true}"#).await?;
+
+	let live = Live::builder()
+		.store(Arc::new(Store::<AppConfig>::new()))
+		.loader(loader(dir_path))
+		.key("app")
+		.profile("production")
+		.profile_env("LIVE_TEST_LAYERS_PROFILE")
+		.build()?;
+
+	live.load().await?;
+	assert!(!live.get().unwrap().debug);
+
+	set_var(&env, "LIVE_TEST_LAYERS_PROFILE", "staging");
+	live.reload().await?;
+	assert!(live.get().unwrap().debug);
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_profile_watch_overlay() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+	let base = dir_path.join("app.json");
+	let overlay = dir_path.join("app.production.json");
+	tokio::fs::write(
+		&base,
+		br#"{"name": "base", "debug": false, "db": {"host": "localhost", "port": 1}}"#,
+	)
+	.await?;
+	tokio::fs::write(&overlay, br#"{"db": {"port": 2}}"#).await?;
+
+	let live = Live::builder()
+		.store(Arc::new(Store::<AppConfig>::new()))
+		.loader(loader(dir_path))
+		.key("app")
+		.root(dir_path)
+		.profile("production")
+		.build()?;
+
+	live.load().await?;
+	assert_eq!(live.get().unwrap().db.port, 2);
+
+	let live = live.watch(live::signal::Config::default()).await?;
+
+	tokio::fs::write(&overlay, br#"{"db": {"port": 3}}"#).await?;
+	for _ in 0..50 {
+		tokio::time::sleep(Duration::from_millis(100)).await;
+		if live.get().unwrap().db.port == 3 {
+			break;
+		}
+	}
+	assert_eq!(live.get().unwrap().db.port, 3);
+
+	tokio::fs::write(
+		&base,
+		br#"{"name": "edited", "debug": false, "db": {"host": "localhost", "port": 1}}"#,
+	)
+	.await?;
+	for _ in 0..50 {
+		tokio::time::sleep(Duration::from_millis(100)).await;
+		if live.get().unwrap().name == "edited" {
+			break;
+		}
+	}
+	let config = live.get().unwrap();
+	assert_eq!(config.name, "edited");
+	assert_eq!(config.db.port, 3);
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_watch_overlays_created_later() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+	tokio::fs::write(
+		dir_path.join("app.json"),
+		br#"{"name": "base", "debug": false, "db": {"host": "localhost", "port": 1}}"#,
+	)
+	.await?;
+
+	let live = Live::builder()
+		.store(Arc::new(Store::<AppConfig>::new()))
+		.loader(loader(dir_path))
+		.key("app")
+		.root(dir_path)
+		.profile("prod")
+		.layer(Layer::file("local"))
+		.build()?;
+
+	live.load().await?;
+	let live = live.watch(live::signal::Config::default()).await?;
+
+	tokio::fs::write(dir_path.join("app.prod.json"), br#"{"db": {"port": 2}}"#).await?;
+	for _ in 0..50 {
+		tokio::time::sleep(Duration::from_millis(100)).await;
+		if live.get().unwrap().db.port == 2 {
+			break;
+		}
+	}
+	assert_eq!(live.get().unwrap().db.port, 2);
+
+	tokio::fs::write(dir_path.join("local.json"), br#"{"debug": true}"#).await?;
+	for _ in 0..50 {
+		tokio::time::sleep(Duration::from_millis(100)).await;
+		if live.get().unwrap().debug {
+			break;
+		}
+	}
+	assert!(live.get().unwrap().debug);
+
+	Ok(())
+}
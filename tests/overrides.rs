@@ -0,0 +1,95 @@
+/* tests/overrides.rs */
+
+#![cfg(feature = "full")]
+
+mod common;
+
+use common::{ListenerConfig, listener, loader};
+use live::controller::{Live, LiveDir};
+use live::holder::Store;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_live_override_holds_back_file_changes() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let file = dir.path().join("app.json");
+	tokio::fs::write(&file, br#"{"bind": ":443"}"#).await?;
+
+	let live = Live::builder()
+		.store(Arc::new(Store::<ListenerConfig>::new()))
+		.loader(loader(dir.path()))
+		.key("app")
+		.build()?;
+	live.load().await?;
+
+	assert!(live.set_override(listener("")).is_err());
+	live.set_override(listener(":9443"))?;
+	assert_eq!(live.get().unwrap().bind, ":9443");
+	assert_eq!(live.get_override().unwrap().value.bind, ":9443");
+
+	// File changes are validated but not published
+	tokio::fs::write(&file, br#"{"bind": ""}"#).await?;
+	assert!(live.reload().await.is_err());
+	tokio::fs::write(&file, br#"{"bind": ":8443"}"#).await?;
+	let diff = live.reload().await?;
+	assert!(!diff.is_empty());
+	assert_eq!(live.get().unwrap().bind, ":9443");
+
+	assert!(live.clear_override());
+	assert!(!live.clear_override());
+	assert!(live.get_override().is_none());
+	assert_eq!(live.get().unwrap().bind, ":8443");
+
+	// A TTL restores the file value on its own
+	live.set_override_for(listener(":9443"), Duration::from_millis(50))?;
+	assert!(live.get_override().unwrap().expires_at.is_some());
+	tokio::time::sleep(Duration::from_millis(200)).await;
+	assert!(live.get_override().is_none());
+	assert_eq!(live.get().unwrap().bind, ":8443");
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_live_dir_overrides() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+	tokio::fs::write(dir_path.join("a.json"), br#"{"bind": ":443"}"#).await?;
+	tokio::fs::write(dir_path.join("b.json"), br#"{"bind": ":80"}"#).await?;
+
+	let live_dir = LiveDir::builder()
+		.store(Arc::new(Store::<ListenerConfig>::new()))
+		.loader(loader(dir_path))
+		.path(dir_path)
+		.build()?;
+	live_dir.load().await?;
+
+	live_dir.set_override("a", listener(":1"))?;
+	live_dir.set_override("b", listener(":2"))?;
+	live_dir.set_override("c", listener(":3"))?;
+	let keys: Vec<String> = live_dir.overrides().into_iter().map(|o| o.key).collect();
+	assert_eq!(keys, vec!["a", "b", "c"]);
+
+	tokio::fs::write(dir_path.join("a.json"), br#"{"bind": ":8443"}"#).await?;
+	tokio::fs::remove_file(dir_path.join("b.json")).await?;
+	let result = live_dir.reload().await?;
+	assert!(result.updated.is_empty());
+	assert!(result.removed.is_empty());
+	assert_eq!(result.overridden, vec!["a", "b"]);
+	assert_eq!(live_dir.get("a").unwrap().bind, ":1");
+	assert_eq!(live_dir.get("b").unwrap().bind, ":2");
+
+	// Unchanged files stay unchanged while overridden
+	let result = live_dir.reload().await?;
+	assert!(result.overridden.is_empty());
+
+	assert!(live_dir.clear_override("a"));
+	assert_eq!(live_dir.get("a").unwrap().bind, ":8443");
+	assert_eq!(live_dir.clear_overrides(), vec!["b", "c"]);
+	assert!(live_dir.get("b").is_none());
+	assert!(live_dir.get("c").is_none());
+	assert!(live_dir.overrides().is_empty());
+
+	Ok(())
+}
@@ -0,0 +1,72 @@
+/* tests/periodic_rescan.rs */
+
+#![cfg(feature = "full")]
+
+mod common;
+
+use common::{AppConfig, deaf, loader, wait_until};
+use live::controller::{Live, LiveDir};
+use live::holder::Store;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_live_rescans_without_events() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let file = dir.path().join("app.json");
+	tokio::fs::write(&file, br#"{"port": 1}"#).await?;
+
+	let errors = Arc::new(Mutex::new(Vec::new()));
+	let sink = Arc::clone(&errors);
+	let live = Live::builder()
+		.store(Arc::new(Store::<AppConfig>::new()))
+		.loader(loader(dir.path()))
+		.key("app")
+		.root(dir.path())
+		.rescan_interval(Duration::from_millis(200))
+		.rescan_jitter(Duration::from_millis(50))
+		.on_error(move |e| sink.lock().unwrap().push(e.to_string()))
+		.build()?;
+	live.load().await?;
+	let live = live.watch(deaf()).await?;
+
+	tokio::fs::write(&file, br#"{"port": 2}"#).await?;
+	assert!(wait_until(|| live.get().unwrap().port == 2).await);
+
+	// Failures are reported like those of event-driven reloads
+	tokio::fs::write(&file, br#"{"port": 0}"#).await?;
+	assert!(wait_until(|| !errors.lock().unwrap().is_empty()).await);
+	assert_eq!(live.get().unwrap().port, 2);
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_live_dir_rescans_without_events() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let live_dir = LiveDir::builder()
+		.store(Arc::new(Store::<AppConfig>::new()))
+		.loader(loader(dir.path()))
+		.path(dir.path())
+		.rescan_interval(Duration::from_millis(200))
+		.build()?;
+	live_dir.load().await?;
+	let live_dir = live_dir.watch(deaf()).await?;
+
+	tokio::fs::write(dir.path().join("a.json"), br#"{"port": 1}"#).await?;
+	assert!(wait_until(|| live_dir.get("a").is_some()).await);
+	tokio::fs::remove_file(dir.path().join("a.json")).await?;
+	assert!(wait_until(|| live_dir.get("a").is_none()).await);
+
+	assert!(
+		LiveDir::<AppConfig>::builder()
+			.store(Arc::new(Store::new()))
+			.loader(loader(dir.path()))
+			.path(dir.path())
+			.rescan_interval(Duration::ZERO)
+			.build()
+			.is_err()
+	);
+
+	Ok(())
+}
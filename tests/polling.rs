@@ -0,0 +1,71 @@
+/* tests/polling.rs */
+
+#![cfg(feature = "full")]
+
+mod common;
+
+use common::{AppConfig, deaf, loader, wait_until};
+use live::controller::{Live, LiveDir, WatchStatus};
+use live::holder::Store;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_live_polls_source() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let file = dir.path().join("app.json");
+	tokio::fs::write(&file, br#"{"port": 1}"#).await?;
+
+	let live = Live::builder()
+		.store(Arc::new(Store::<AppConfig>::new()))
+		.loader(loader(dir.path()))
+		.key("app")
+		.root(dir.path())
+		.poll_interval(Duration::from_millis(100))
+		.build()?;
+	live.load().await?;
+	let live = live.watch(deaf()).await?;
+	assert!(wait_until(|| live.watch_status() == Some(WatchStatus::Polling)).await);
+
+	// Same size, so only the mtime or the content hash tells them apart
+	tokio::fs::write(&file, br#"{"port": 2}"#).await?;
+	assert!(wait_until(|| live.get().unwrap().port == 2).await);
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_live_dir_polls_entries() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	tokio::fs::write(dir.path().join("a.json"), br#"{"port": 1}"#).await?;
+
+	let live_dir = LiveDir::builder()
+		.store(Arc::new(Store::<AppConfig>::new()))
+		.loader(loader(dir.path()))
+		.path(dir.path())
+		.poll_interval(Duration::from_millis(100))
+		.build()?;
+	live_dir.load().await?;
+	let live_dir = live_dir.watch(deaf()).await?;
+	assert!(wait_until(|| live_dir.watch_status() == Some(WatchStatus::Polling)).await);
+
+	tokio::fs::write(dir.path().join("a.json"), br#"{"port": 2}"#).await?;
+	tokio::fs::write(dir.path().join("b.json"), br#"{"port": 3}"#).await?;
+	assert!(wait_until(|| live_dir.get("a").is_some_and(|a| a.port == 2)).await);
+	assert!(wait_until(|| live_dir.get("b").is_some()).await);
+
+	tokio::fs::remove_file(dir.path().join("b.json")).await?;
+	assert!(wait_until(|| live_dir.get("b").is_none()).await);
+
+	assert!(
+		LiveDir::<AppConfig>::builder()
+			.store(Arc::new(Store::new()))
+			.loader(loader(dir.path()))
+			.path(dir.path())
+			.poll_interval(Duration::ZERO)
+			.build()
+			.is_err()
+	);
+
+	Ok(())
+}
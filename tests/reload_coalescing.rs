@@ -0,0 +1,65 @@
+/* tests/reload_coalescing.rs */
+
+#![cfg(feature = "full")]
+
+mod common;
+
+use common::{AppConfig, loader};
+use live::controller::{LiveDir, ReloadState};
+use live::holder::Store;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_live_dir_reloads_are_serialized_and_coalesced()
+-> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let file = dir.path().join("a.json");
+	tokio::fs::write(&file, br#"{"port": 1}"#).await?;
+
+	let applied = Arc::new(AtomicUsize::new(0));
+	let counter = Arc::clone(&applied);
+	let live_dir = LiveDir::builder()
+		.store(Arc::new(Store::<AppConfig>::new()))
+		.loader(loader(dir.path()))
+		.path(dir.path())
+		// Slow enough to pile up reloads behind it
+		.on_apply(move |_, _| {
+			counter.fetch_add(1, Ordering::SeqCst);
+			async {
+				tokio::time::sleep(Duration::from_millis(200)).await;
+				Ok::<_, String>(())
+			}
+		})
+		.build()?;
+	assert_eq!(live_dir.reload_state(), ReloadState::default());
+
+	let first = tokio::spawn({
+		let live_dir = live_dir.clone();
+		async move { live_dir.load().await }
+	});
+	tokio::time::sleep(Duration::from_millis(50)).await;
+	assert!(live_dir.reload_state().in_progress);
+
+	tokio::fs::write(&file, br#"{"port": 2}"#).await?;
+	let waiting: Vec<_> = (0..5)
+		.map(|_| {
+			let live_dir = live_dir.clone();
+			tokio::spawn(async move { live_dir.reload().await })
+		})
+		.collect();
+	tokio::time::sleep(Duration::from_millis(50)).await;
+	assert_eq!(live_dir.reload_state().pending, 5);
+
+	assert_eq!(first.await??.added, vec!["a"]);
+	for handle in waiting {
+		assert_eq!(handle.await??.updated, vec!["a"]);
+	}
+	// One load plus a single follow-up for all five reloads
+	assert_eq!(applied.load(Ordering::SeqCst), 2);
+	assert_eq!(live_dir.get("a").unwrap().port, 2);
+	assert_eq!(live_dir.reload_state(), ReloadState::default());
+
+	Ok(())
+}
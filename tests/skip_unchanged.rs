@@ -0,0 +1,98 @@
+/* tests/skip_unchanged.rs */
+
+#![cfg(feature = "full")]
+
+mod common;
+
+use common::{ListenerConfig, loader};
+use live::controller::{Live, LiveDir};
+use live::holder::Store;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_live_dir_skips_identical_entries() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+	tokio::fs::write(dir_path.join("443.json"), br#"{"bind": ":443"}"#).await?;
+	tokio::fs::write(dir_path.join("80.json"), br#"{"bind": ":80"}"#).await?;
+
+	let store = Arc::new(Store::<ListenerConfig>::new());
+	let live_dir = LiveDir::builder()
+		.store(store.clone())
+		.loader(loader(dir_path))
+		.path(dir_path)
+		.build()?;
+	live_dir.load().await?;
+	let version = store.get_meta("80").unwrap().version;
+
+	let mut events = live_dir.subscribe();
+	tokio::fs::write(
+		dir_path.join("443.json"),
+		br#"{"bind": ":443", "tls": true}"#,
+	)
+	.await?;
+	let result = live_dir.reload().await?;
+
+	assert_eq!(result.updated, vec!["443"]);
+	assert_eq!(result.unchanged, vec!["80"]);
+	assert_eq!(result.loaded().count(), 2);
+	assert_eq!(store.get_meta("80").unwrap().version, version);
+
+	// Only the real change reaches subscribers
+	assert!(events.try_recv().is_ok());
+	assert!(events.try_recv().is_err());
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_skip_equal_ignores_irrelevant_changes() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+	tokio::fs::write(dir_path.join("a.json"), br#"{"bind": ":443"}"#).await?;
+
+	let store = Arc::new(Store::<ListenerConfig>::new());
+	let live_dir = LiveDir::builder()
+		.store(store.clone())
+		.loader(loader(dir_path))
+		.path(dir_path)
+		.skip_equal()
+		.build()?;
+	live_dir.load().await?;
+
+	// An unknown field changes the content but not the decoded value
+	tokio::fs::write(dir_path.join("a.json"), br#"{"bind": ":443", "note": "x"}"#).await?;
+	let result = live_dir.reload().await?;
+	assert_eq!(result.unchanged, vec!["a"]);
+	assert!(result.updated.is_empty());
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_live_skips_identical_reload() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+	tokio::fs::write(dir_path.join("app.json"), br#"{"bind": ":443"}"#).await?;
+
+	let store = Arc::new(Store::<ListenerConfig>::new());
+	let live = Live::builder()
+		.store(store.clone())
+		.loader(loader(dir_path))
+		.key("app")
+		.build()?;
+	live.load().await?;
+	let version = store.get_meta("app").unwrap().version;
+
+	let mut events = live.subscribe();
+	assert!(live.reload().await?.is_empty());
+	assert_eq!(store.get_meta("app").unwrap().version, version);
+	assert!(events.try_recv().is_err());
+
+	tokio::fs::write(dir_path.join("app.json"), br#"{"bind": ":8443"}"#).await?;
+	assert!(!live.reload().await?.is_empty());
+	assert!(store.get_meta("app").unwrap().version > version);
+	assert!(events.try_recv().is_ok());
+
+	Ok(())
+}
@@ -0,0 +1,112 @@
+/* tests/validate_all.rs */
+
+#![cfg(feature = "full")]
+
+mod common;
+
+use common::{ListenerConfig, loader};
+use live::controller::{LiveDir, Rejection};
+use live::holder::Store;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Rejects every key but the first one binding an address.
+fn unique_binds(entries: &HashMap<String, Arc<ListenerConfig>>) -> Result<(), Rejection> {
+	let mut keys: Vec<&String> = entries.keys().collect();
+	keys.sort();
+	let mut seen: HashMap<&str, &str> = HashMap::new();
+	let mut rejected = Vec::new();
+	for key in keys {
+		let bind = entries[key].bind.as_str();
+		match seen.get(bind) {
+			Some(first) => rejected.push((
+				key.clone(),
+				format!("{} is already bound by {}", bind, first),
+			)),
+			None => {
+				seen.insert(bind, key);
+			}
+		}
+	}
+	if rejected.is_empty() {
+		Ok(())
+	} else {
+		Err(Rejection::Keys(rejected))
+	}
+}
+
+#[tokio::test]
+async fn test_validate_all_rejects_conflicting_keys() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+	tokio::fs::write(dir_path.join("a.json"), br#"{"bind": ":443"}"#).await?;
+	tokio::fs::write(dir_path.join("b.json"), br#"{"bind": ":80"}"#).await?;
+
+	let live_dir = LiveDir::builder()
+		.store(Arc::new(Store::<ListenerConfig>::new()))
+		.loader(loader(dir_path))
+		.path(dir_path)
+		.validate_all(unique_binds)
+		.build()?;
+	live_dir.load().await?;
+
+	// b moves onto a's address and a new entry c does the same
+	tokio::fs::write(dir_path.join("b.json"), br#"{"bind": ":443"}"#).await?;
+	tokio::fs::write(dir_path.join("c.json"), br#"{"bind": ":443"}"#).await?;
+	let result = live_dir.reload().await?;
+
+	assert!(result.added.is_empty());
+	assert!(result.updated.is_empty());
+	assert_eq!(result.unchanged, vec!["a"]);
+	assert_eq!(result.failed.len(), 2);
+	assert_eq!(
+		result.failed[0],
+		("b".to_string(), ":443 is already bound by a".to_string())
+	);
+	assert_eq!(result.failed[1].0, "c");
+
+	// Rejected keys keep their old value, new ones are not added
+	assert_eq!(live_dir.get("b").unwrap().bind, ":80");
+	assert!(live_dir.get("c").is_none());
+
+	tokio::fs::write(dir_path.join("c.json"), br#"{"bind": ":8443"}"#).await?;
+	tokio::fs::write(dir_path.join("b.json"), br#"{"bind": ":80"}"#).await?;
+	let result = live_dir.reload().await?;
+	assert!(result.failed.is_empty());
+	assert_eq!(result.added, vec!["c"]);
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_validate_all_rejects_whole_set() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+	tokio::fs::write(dir_path.join("a.json"), br#"{"bind": ":443"}"#).await?;
+	tokio::fs::write(dir_path.join("b.json"), br#"{"bind": ":80"}"#).await?;
+
+	let live_dir = LiveDir::builder()
+		.store(Arc::new(Store::<ListenerConfig>::new()))
+		.loader(loader(dir_path))
+		.path(dir_path)
+		.validate_all(|entries| match entries.len() {
+			0 => Err(Rejection::All(
+				"at least one listener is required".to_string(),
+			)),
+			_ => Ok(()),
+		})
+		.build()?;
+	live_dir.load().await?;
+
+	tokio::fs::remove_file(dir_path.join("a.json")).await?;
+	tokio::fs::remove_file(dir_path.join("b.json")).await?;
+	let result = live_dir.reload().await?;
+
+	assert!(result.removed.is_empty());
+	assert_eq!(result.failed.len(), 2);
+	assert_eq!(result.failed[0].1, "at least one listener is required");
+	assert_eq!(live_dir.len().await, 2);
+	assert!(live_dir.get("a").is_some());
+
+	Ok(())
+}
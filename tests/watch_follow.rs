@@ -0,0 +1,124 @@
+/* tests/watch_follow.rs */
+
+#![cfg(feature = "full")]
+
+mod common;
+
+use common::{AppConfig, loader};
+use live::controller::{Live, LiveDir};
+use live::holder::Store;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn config() -> live::signal::Config {
+	live::signal::Config {
+		debounce: Duration::from_millis(50),
+		..Default::default()
+	}
+}
+
+async fn wait_for_port(live: &Live<AppConfig>, port: u16) -> bool {
+	for _ in 0..50 {
+		if live.get().is_some_and(|c| c.port == port) {
+			return true;
+		}
+		tokio::time::sleep(Duration::from_millis(100)).await;
+	}
+	false
+}
+
+#[tokio::test]
+async fn test_live_follows_replaced_file() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let file = dir.path().join("app.json");
+	tokio::fs::write(&file, br#"{"port": 1}"#).await?;
+
+	let live = Live::builder()
+		.store(Arc::new(Store::<AppConfig>::new()))
+		.loader(loader(dir.path()))
+		.key("app")
+		.root(dir.path())
+		.build()?;
+	live.load().await?;
+	let live = live.watch(config()).await?;
+
+	// Replaced through a rename, as editors and deploy tools do
+	let tmp = dir.path().join("app.json.new");
+	tokio::fs::write(&tmp, br#"{"port": 2}"#).await?;
+	tokio::fs::rename(&tmp, &file).await?;
+	assert!(wait_for_port(&live, 2).await);
+
+	// Deleted and recreated
+	tokio::fs::remove_file(&file).await?;
+	tokio::time::sleep(Duration::from_millis(200)).await;
+	tokio::fs::write(&file, br#"{"port": 3}"#).await?;
+	assert!(wait_for_port(&live, 3).await);
+
+	// Switched to another format, then edited there
+	tokio::fs::remove_file(&file).await?;
+	tokio::fs::write(dir.path().join("app.toml"), "port = 4\n").await?;
+	assert!(wait_for_port(&live, 4).await);
+	tokio::time::sleep(Duration::from_millis(200)).await;
+	tokio::fs::write(dir.path().join("app.toml"), "port = 5\n").await?;
+	assert!(wait_for_port(&live, 5).await);
+
+	// Unrelated files in the directory are ignored
+	tokio::fs::write(dir.path().join("other.json"), br#"{"port": 6}"#).await?;
+	tokio::time::sleep(Duration::from_millis(300)).await;
+	assert_eq!(live.get().unwrap().port, 5);
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_live_watches_missing_file() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let conf_dir = dir.path().join("conf");
+	let live = Live::builder()
+		.store(Arc::new(Store::<AppConfig>::new()))
+		.loader(loader(&conf_dir))
+		.key("app")
+		.root(&conf_dir)
+		.build()?;
+	assert!(live.load().await.is_err());
+	let live = live.watch(config()).await?;
+
+	// Neither the file nor its directory exist yet
+	tokio::time::sleep(Duration::from_millis(100)).await;
+	tokio::fs::create_dir(&conf_dir).await?;
+	tokio::time::sleep(Duration::from_millis(200)).await;
+	tokio::fs::write(conf_dir.join("app.json"), br#"{"port": 1}"#).await?;
+	assert!(wait_for_port(&live, 1).await);
+
+	tokio::fs::write(conf_dir.join("app.json"), br#"{"port": 2}"#).await?;
+	assert!(wait_for_port(&live, 2).await);
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_live_dir_watches_missing_dir() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let entries = dir.path().join("a").join("b");
+	let live_dir = LiveDir::builder()
+		.store(Arc::new(Store::<AppConfig>::new()))
+		.loader(loader(&entries))
+		.path(&entries)
+		.build()?;
+	let live_dir = live_dir.watch(config()).await?;
+
+	tokio::time::sleep(Duration::from_millis(100)).await;
+	tokio::fs::create_dir_all(&entries).await?;
+	tokio::fs::write(entries.join("x.json"), br#"{"port": 1}"#).await?;
+	let mut found = false;
+	for _ in 0..50 {
+		if live_dir.get("x").is_some_and(|c| c.port == 1) {
+			found = true;
+			break;
+		}
+		tokio::time::sleep(Duration::from_millis(100)).await;
+	}
+	assert!(found);
+
+	Ok(())
+}
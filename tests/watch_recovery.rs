@@ -0,0 +1,104 @@
+/* tests/watch_recovery.rs */
+
+#![cfg(feature = "full")]
+
+mod common;
+
+use common::{AppConfig, loader, wait_until};
+use live::controller::{LiveDir, WatchStatus};
+use live::holder::Store;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+fn live_dir(
+	dir: &std::path::Path,
+	errors: &Arc<Mutex<Vec<String>>>,
+) -> Result<LiveDir<AppConfig>, Box<dyn std::error::Error>> {
+	let errors = Arc::clone(errors);
+	Ok(
+		LiveDir::builder()
+			.store(Arc::new(Store::<AppConfig>::new()))
+			.loader(loader(dir))
+			.path(dir)
+			.on_error(move |e| errors.lock().unwrap().push(e.to_string()))
+			.build()?,
+	)
+}
+
+#[tokio::test]
+async fn test_live_dir_reloads_everything_after_lag() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let errors = Arc::new(Mutex::new(Vec::new()));
+	let live_dir = live_dir(dir.path(), &errors)?;
+	live_dir.load().await?;
+	let config = live::signal::Config {
+		debounce: Duration::from_millis(50),
+		channel_capacity: 1,
+		..Default::default()
+	};
+	let live_dir = live_dir.watch(config).await?;
+
+	// Far more events than the channel holds
+	for i in 0..20 {
+		tokio::fs::write(dir.path().join(format!("{}.json", i)), br#"{"port": 1}"#).await?;
+	}
+	assert!(wait_until(|| (0..20).all(|i| live_dir.get(&i.to_string()).is_some())).await);
+	assert!(wait_until(|| live_dir.watch_status() == Some(WatchStatus::Active)).await);
+	assert!(
+		errors
+			.lock()
+			.unwrap()
+			.iter()
+			.any(|e| e.starts_with("Watch events dropped"))
+	);
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_live_dir_recreates_dead_watcher() -> Result<(), Box<dyn std::error::Error>> {
+	let root = tempfile::tempdir()?;
+	let dir = root.path().join("conf");
+	tokio::fs::create_dir(&dir).await?;
+	tokio::fs::write(dir.join("a.json"), br#"{"port": 1}"#).await?;
+	let errors = Arc::new(Mutex::new(Vec::new()));
+	let live_dir = live_dir(&dir, &errors)?;
+	live_dir.load().await?;
+	let config = live::signal::Config {
+		debounce: Duration::from_millis(50),
+		..Default::default()
+	};
+	let live_dir = live_dir.watch(config).await?;
+	assert_eq!(live_dir.watch_status(), Some(WatchStatus::Active));
+
+	// Removing the watched directory ends its watcher
+	tokio::fs::remove_dir_all(&dir).await?;
+	assert!(
+		wait_until(|| matches!(live_dir.watch_status(), Some(WatchStatus::Retrying { .. }))).await
+	);
+	assert!(
+		errors
+			.lock()
+			.unwrap()
+			.iter()
+			.any(|e| e.starts_with("Watcher stopped"))
+	);
+
+	tokio::fs::create_dir(&dir).await?;
+	tokio::fs::write(dir.join("a.json"), br#"{"port": 2}"#).await?;
+	assert!(wait_until(|| live_dir.get("a").is_some_and(|c| c.port == 2)).await);
+	assert!(wait_until(|| live_dir.watch_status() == Some(WatchStatus::Active)).await);
+	assert!(
+		errors
+			.lock()
+			.unwrap()
+			.iter()
+			.any(|e| e.starts_with("Watcher restored"))
+	);
+
+	// Watching works again
+	tokio::fs::write(dir.join("b.json"), br#"{"port": 3}"#).await?;
+	assert!(wait_until(|| live_dir.get("b").is_some()).await);
+
+	Ok(())
+}
@@ -0,0 +1,152 @@
+/* tests/write_back.rs */
+
+#![cfg(feature = "full")]
+
+mod common;
+
+use common::{ListenerConfig, listener, loader, lock_env, set_var};
+use live::controller::{EnvOverrides, Live, LiveDir, LiveError};
+use live::holder::Store;
+use live::loader::format::AnyFormat;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_live_update_writes_source_file() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let file = dir.path().join("app.toml");
+	tokio::fs::write(&file, "bind = \":443\"\n").await?;
+
+	let live = Live::builder()
+		.store(Arc::new(Store::<ListenerConfig>::new()))
+		.loader(loader(dir.path()))
+		.key("app")
+		.root(dir.path())
+		.build()?;
+	assert!(matches!(
+		live.update(|cfg| cfg.workers = 4).await,
+		Err(LiveError::NotLoaded)
+	));
+	live.load().await?;
+
+	let diff = live.update(|cfg| cfg.workers = 4).await?;
+	assert!(diff.get("workers").is_some());
+	assert_eq!(live.get().unwrap().workers, 4);
+	let written: toml::Value = toml::from_str(&tokio::fs::read_to_string(&file).await?)?;
+	assert_eq!(written["workers"].as_integer(), Some(4));
+
+	// Invalid values never reach the file
+	assert!(live.update(|cfg| cfg.bind.clear()).await.is_err());
+	assert!(tokio::fs::read_to_string(&file).await?.contains(":443"));
+	assert_eq!(live.get().unwrap().bind, ":443");
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_live_update_writes_base_fields_only() -> Result<(), Box<dyn std::error::Error>> {
+	let env = lock_env().await;
+	let dir = tempfile::tempdir()?;
+	let file = dir.path().join("app.json");
+	tokio::fs::write(&file, br#"{"bind": ":443"}"#).await?;
+
+	let live = Live::builder()
+		.store(Arc::new(Store::<ListenerConfig>::new()))
+		.loader(loader(dir.path()))
+		.key("app")
+		.root(dir.path())
+		.defaults(&listener(":80"))
+		.env(EnvOverrides::new("LIVE_TEST_WRITE_BACK"))
+		.build()?;
+	set_var(&env, "LIVE_TEST_WRITE_BACK__WORKERS", "9");
+	live.load().await?;
+	assert_eq!(live.get().unwrap().workers, 9);
+
+	live.update(|cfg| cfg.bind = ":8443".to_string()).await?;
+	let written: serde_json::Value = serde_json::from_slice(&tokio::fs::read(&file).await?)?;
+	assert_eq!(written, serde_json::json!({"bind": ":8443"}));
+	assert_eq!(live.get().unwrap().workers, 9);
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_live_dir_upsert_writes_entry_fields_only() -> Result<(), Box<dyn std::error::Error>> {
+	let env = lock_env().await;
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+	let file = dir_path.join("a.json");
+	tokio::fs::write(&file, br#"{"bind": "${LIVE_TEST_UPSERT_BIND}"}"#).await?;
+
+	let live_dir = LiveDir::builder()
+		.store(Arc::new(Store::<ListenerConfig>::new()))
+		.loader(loader(dir_path))
+		.path(dir_path)
+		.env(EnvOverrides::new("LIVE_TEST_UPSERT"))
+		.interpolate(true)
+		.build()?;
+	set_var(&env, "LIVE_TEST_UPSERT_BIND", ":443");
+	set_var(&env, "LIVE_TEST_UPSERT__A__WORKERS", "7");
+	live_dir.load().await?;
+
+	// Unchanged values leave the file as it is
+	live_dir
+		.upsert("a", (*live_dir.get("a").unwrap()).clone())
+		.await?;
+	let written: serde_json::Value = serde_json::from_slice(&tokio::fs::read(&file).await?)?;
+	assert_eq!(
+		written,
+		serde_json::json!({"bind": "${LIVE_TEST_UPSERT_BIND}"})
+	);
+
+	let mut value = (*live_dir.get("a").unwrap()).clone();
+	value.tls = true;
+	live_dir.upsert("a", value).await?;
+	let written: serde_json::Value = serde_json::from_slice(&tokio::fs::read(&file).await?)?;
+	assert_eq!(
+		written,
+		serde_json::json!({"bind": "${LIVE_TEST_UPSERT_BIND}", "tls": true})
+	);
+	let a = live_dir.get("a").unwrap();
+	assert_eq!((a.bind.as_str(), a.workers, a.tls), (":443", 7, true));
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_live_dir_upsert_and_delete() -> Result<(), Box<dyn std::error::Error>> {
+	let dir = tempfile::tempdir()?;
+	let dir_path = dir.path();
+	tokio::fs::write(dir_path.join("a.json"), br#"{"bind": ":443"}"#).await?;
+
+	let live_dir = LiveDir::builder()
+		.store(Arc::new(Store::<ListenerConfig>::new()))
+		.loader(loader(dir_path))
+		.path(dir_path)
+		.write_format(AnyFormat::Toml)
+		.build()?;
+	live_dir.load().await?;
+
+	// Existing entries keep their format
+	let result = live_dir.upsert("a", listener(":8443")).await?;
+	assert_eq!(result.updated, vec!["a"]);
+	let written: serde_json::Value =
+		serde_json::from_slice(&tokio::fs::read(dir_path.join("a.json")).await?)?;
+	assert_eq!(written["bind"], ":8443");
+
+	let result = live_dir.upsert("b", listener(":80")).await?;
+	assert_eq!(result.added, vec!["b"]);
+	assert!(dir_path.join("b.toml").exists());
+	assert_eq!(live_dir.get("b").unwrap().bind, ":80");
+
+	assert!(live_dir.upsert("../c", listener(":1")).await.is_err());
+	assert!(live_dir.upsert("c", listener("")).await.is_err());
+	assert!(!dir_path.join("c.toml").exists());
+
+	let result = live_dir.delete("a").await?;
+	assert_eq!(result.removed, vec!["a"]);
+	assert!(!dir_path.join("a.json").exists());
+	assert!(live_dir.get("a").is_none());
+	assert!(live_dir.delete("a").await.is_err());
+
+	Ok(())
+}